@@ -1,8 +1,16 @@
 //! Lobby screen controller for displaying players and starting the game.
+//!
+//! Room updates arrive over the server's WebSocket push channel
+//! (`/rooms/:room_id/ws`). While the socket is down the screen falls back to
+//! polling `GET /rooms/:room_id` and keeps trying to reconnect.
+//!
+//! The socket carries the session token, which keeps this screen marked as
+//! connected; while it is down the poll timer sends heartbeats instead.
 
 use godot::prelude::*;
-use godot::classes::{Control, IControl, Label, Button, VBoxContainer, Timer, ITimer, HttpRequest};
+use godot::classes::{Control, IControl, Label, Button, VBoxContainer, Timer, ITimer, HttpRequest, WebSocketPeer};
 use godot::classes::http_client::Method;
+use godot::classes::web_socket_peer::State as SocketState;
 
 #[derive(GodotClass)]
 #[class(base=Control)]
@@ -21,10 +29,20 @@ pub struct LobbyScreen {
     #[var]
     player_id: GString,
     
+    /// Secret from the join response, sent as a bearer token.
+    #[var]
+    session_token: GString,
+    
     #[var]
     is_host: bool,
 
     poll_timer: Option<Gd<Timer>>,
+    
+    /// Push channel; `None` while disconnected.
+    socket: Option<Gd<WebSocketPeer>>,
+    
+    /// Players as last reported by a snapshot and the events since.
+    players: Vec<serde_json::Value>,
 }
 
 #[godot_api]
@@ -37,11 +55,18 @@ impl IControl for LobbyScreen {
             room_id: "".into(),
             room_code: "".into(),
             player_id: "".into(),
+            session_token: "".into(),
             is_host: false,
             poll_timer: None,
+            socket: None,
+            players: Vec::new(),
         }
     }
     
+    fn process(&mut self, _delta: f64) {
+        self.read_socket();
+    }
+    
     fn ready(&mut self) {
         godot_print!("LobbyScreen ready");
         godot_print!("Room: {}, Player: {}, Host: {}", self.room_code, self.player_id, self.is_host);
@@ -51,6 +76,10 @@ impl IControl for LobbyScreen {
         poll_request.set_name("PollRequest");
         self.base_mut().add_child(&poll_request);
         
+        let mut heartbeat_request = HttpRequest::new_alloc();
+        heartbeat_request.set_name("HeartbeatRequest");
+        self.base_mut().add_child(&heartbeat_request);
+        
         // Create timer for polling room state every 2 seconds
         let mut timer = Timer::new_alloc();
         timer.set_name("PollTimer");
@@ -71,19 +100,20 @@ impl IControl for LobbyScreen {
         // Update UI with initial info
         self.update_room_info();
         
-        // Do initial poll
-        self.poll_room_state();
+        // Subscribe to room updates
+        self.connect_socket();
     }
 }
 
 #[godot_api]
 impl LobbyScreen {
     #[func]
-    fn set_room_info(&mut self, room_id: GString, room_code: GString, player_id: GString, is_host: bool) {
+    fn set_room_info(&mut self, room_id: GString, room_code: GString, player_id: GString, session_token: GString, is_host: bool) {
         godot_print!("Setting room info: room={}, code={}, player={}, host={}", room_id, room_code, player_id, is_host);
         self.room_id = room_id;
         self.room_code = room_code;
         self.player_id = player_id;
+        self.session_token = session_token;
         self.is_host = is_host;
         
         self.update_room_info();
@@ -91,7 +121,103 @@ impl LobbyScreen {
     
     #[func]
     fn on_poll_timer_timeout(&mut self) {
-        self.poll_room_state();
+        // Only poll (and retry the socket) while the push channel is down
+        if self.socket.is_none() {
+            self.poll_room_state();
+            self.send_heartbeat();
+            self.connect_socket();
+        }
+    }
+    
+    fn connect_socket(&mut self) {
+        if self.room_id.is_empty() {
+            return;
+        }
+        
+        let base_url = self.server_url.to_string();
+        let ws_url = match base_url.strip_prefix("https://") {
+            Some(rest) => format!("wss://{}", rest),
+            None => format!("ws://{}", base_url.trim_start_matches("http://")),
+        };
+        let url = format!("{}/rooms/{}/ws?token={}", ws_url, self.room_id, self.session_token);
+        
+        let mut socket = WebSocketPeer::new_gd();
+        let result = socket.connect_to_url(&url);
+        if result != godot::global::Error::OK {
+            godot_warn!("Failed to open room socket: {:?}", result);
+            return;
+        }
+        godot_print!("Connecting to {}", url);
+        self.socket = Some(socket);
+    }
+    
+    fn read_socket(&mut self) {
+        let Some(socket) = self.socket.as_mut() else {
+            return;
+        };
+        socket.poll();
+        
+        let mut messages = Vec::new();
+        match socket.get_ready_state() {
+            SocketState::OPEN => {
+                while socket.get_available_packet_count() > 0 {
+                    let packet = socket.get_packet().to_vec();
+                    messages.push(String::from_utf8_lossy(&packet).into_owned());
+                }
+            }
+            SocketState::CLOSED => {
+                godot_warn!("Room socket closed (code {}), falling back to polling", socket.get_close_code());
+                self.socket = None;
+            }
+            _ => {}
+        }
+        
+        for message in messages {
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&message) {
+                self.apply_push(&json);
+            }
+        }
+    }
+    
+    /// Apply one pushed message: a full snapshot or a change event.
+    fn apply_push(&mut self, message: &serde_json::Value) {
+        match message["type"].as_str().unwrap_or("") {
+            "snapshot" => self.apply_room_state(&message["room"]),
+            "player_joined" => {
+                self.players.push(serde_json::json!({
+                    "id": message["player_id"],
+                    "nickname": message["nickname"],
+                    "connected": true,
+                }));
+                self.refresh_players();
+            }
+            "player_left" => {
+                self.players.retain(|p| p["id"] != message["player_id"]);
+                self.refresh_players();
+            }
+            "game_started" => {
+                godot_print!("Game has started! Transitioning to game screen...");
+                // TODO: Transition to game screen
+            }
+            "host_changed" => self.apply_host(&message["host"]),
+            "connection_changed" => {
+                let participant = &message["participant"];
+                if participant["type"] == "player" {
+                    for player in self.players.iter_mut() {
+                        if player["id"] == participant["player_id"] {
+                            player["connected"] = message["connected"].clone();
+                        }
+                    }
+                    self.refresh_players();
+                }
+            }
+            "room_closed" => {
+                godot_warn!("Room was closed");
+                self.socket = None;
+                self.room_id = "".into();
+            }
+            _ => {}
+        }
     }
     
     #[func]
@@ -105,11 +231,13 @@ impl LobbyScreen {
         
         let url = format!("{}/rooms/{}/start", self.server_url, self.room_id);
         godot_print!("Requesting: POST {}", url);
+        let authorization = format!("Authorization: Bearer {}", self.session_token);
         
         let result = {
             let mut base = self.base_mut();
             if let Some(mut request) = base.try_get_node_as::<HttpRequest>("PollRequest") {
-                let headers = PackedStringArray::new();
+                let mut headers = PackedStringArray::new();
+                headers.push(authorization.as_str());
                 Some(request.request_ex(&url)
                     .custom_headers(&headers)
                     .method(Method::POST)
@@ -127,6 +255,27 @@ impl LobbyScreen {
         }
     }
     
+    /// Tell the server we are still here while the socket is down.
+    fn send_heartbeat(&mut self) {
+        if self.room_id.is_empty() || self.session_token.is_empty() {
+            return;
+        }
+        
+        let url = format!("{}/rooms/{}/heartbeat", self.server_url, self.room_id);
+        let authorization = format!("Authorization: Bearer {}", self.session_token);
+        let mut base = self.base_mut();
+        if let Some(mut request) = base.try_get_node_as::<HttpRequest>("HeartbeatRequest") {
+            let mut headers = PackedStringArray::new();
+            headers.push(authorization.as_str());
+            // A request still in flight just means this beat is skipped
+            let _ = request.request_ex(&url)
+                .custom_headers(&headers)
+                .method(Method::POST)
+                .request_data("")
+                .done();
+        }
+    }
+    
     fn poll_room_state(&mut self) {
         if self.room_id.is_empty() {
             return;
@@ -182,26 +331,48 @@ impl LobbyScreen {
         
         // Parse JSON response
         if let Ok(json) = serde_json::from_str::<serde_json::Value>(&body_str) {
-            let player_count = json["player_count"].as_i64().unwrap_or(0);
-            let state = json["state"].as_str().unwrap_or("Unknown");
-            
-            godot_print!("Room state: {} players, state: {}", player_count, state);
-            
-            // Update player list
-            if let Some(players) = json["players"].as_array() {
-                self.update_player_list(players);
-            }
-            
-            // Enable/disable start button based on player count
-            if self.is_host {
-                self.set_start_button_enabled(player_count >= 2);
-            }
-            
-            // Check if game has started
-            if state == "InGame" {
-                godot_print!("Game has started! Transitioning to game screen...");
-                // TODO: Transition to game screen
-            }
+            self.apply_room_state(&json);
+        }
+    }
+    
+    /// Replace the displayed state with a full room snapshot.
+    fn apply_room_state(&mut self, json: &serde_json::Value) {
+        let state = json["state"].as_str().unwrap_or("Unknown");
+        
+        self.players = json["players"].as_array().cloned().unwrap_or_default();
+        godot_print!("Room state: {} players, state: {}", self.players.len(), state);
+        self.apply_host(&json["host"]);
+        
+        // Check if game has started
+        if state == "InGame" {
+            godot_print!("Game has started! Transitioning to game screen...");
+            // TODO: Transition to game screen
+        }
+    }
+    
+    /// Take or give up host privileges; they move when the host leaves.
+    fn apply_host(&mut self, host: &serde_json::Value) {
+        // The display joins without a player ID
+        let is_host = if self.player_id.is_empty() {
+            host["type"] == "display"
+        } else {
+            host["type"] == "player" && host["player_id"] == self.player_id.to_string().as_str()
+        };
+        if is_host != self.is_host {
+            godot_print!("Host privileges {}", if is_host { "gained" } else { "passed on" });
+            self.is_host = is_host;
+            self.update_room_info();
+        }
+        self.refresh_players();
+    }
+    
+    fn refresh_players(&mut self) {
+        let players = self.players.clone();
+        self.update_player_list(&players);
+        
+        // Enable/disable start button based on player count
+        if self.is_host {
+            self.set_start_button_enabled(players.len() >= 2);
         }
     }
     
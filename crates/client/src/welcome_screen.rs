@@ -16,6 +16,7 @@ pub struct WelcomeScreen {
     room_id: Option<String>,
     room_code: Option<String>,
     player_id: Option<String>,
+    session_token: Option<String>,
     player_nickname: Option<String>,
 }
 
@@ -29,6 +30,7 @@ impl IControl for WelcomeScreen {
             room_id: None,
             room_code: None,
             player_id: None,
+            session_token: None,
             player_nickname: None,
         }
     }
@@ -110,6 +112,8 @@ impl WelcomeScreen {
         // For now, use a random nickname
         let nickname = format!("Player{}", (godot::classes::Time::singleton().get_ticks_msec() % 9999));
         let avatar_id = 0;
+        self.player_nickname = Some(nickname.clone());
+        self.room_code = Some(code.clone());
         
         self.set_status(&format!("Joining room {}...", code), false);
         self.set_button_enabled("JoinButton", false);
@@ -173,12 +177,14 @@ impl WelcomeScreen {
                 if let (Some(room_code), Some(room_id)) = (json["room_code"].as_str(), json["room_id"].as_str()) {
                     self.room_code = Some(room_code.to_string());
                     self.room_id = Some(room_id.to_string());
+                    self.session_token = json["session_token"].as_str().map(str::to_string);
                     
                     self.set_status(&format!("Room created! Code: {}", room_code), false);
                     godot_print!("Room code: {}, Room ID: {}", room_code, room_id);
                     
-                    // Auto-join as host
-                    self.auto_join_as_host(room_code);
+                    // This screen is the room's display and host; it takes no player seat
+                    let (room_id, room_code) = (room_id.to_string(), room_code.to_string());
+                    self.transition_to_lobby(&room_id, &room_code, "", true);
                     return;
                 }
             }
@@ -203,6 +209,7 @@ impl WelcomeScreen {
             if let Ok(json) = serde_json::from_str::<serde_json::Value>(&body_str) {
                 if let (Some(room_id), Some(player_id)) = (json["room_id"].as_str(), json["player_id"].as_str()) {
                     self.player_id = Some(player_id.to_string());
+                    self.session_token = json["session_token"].as_str().map(str::to_string);
                     if self.room_id.is_none() {
                         self.room_id = Some(room_id.to_string());
                     }
@@ -233,27 +240,6 @@ impl WelcomeScreen {
         self.set_button_enabled("JoinButton", true);
     }
     
-    fn auto_join_as_host(&mut self, room_code: &str) {
-        let nickname = format!("Host{}", (godot::classes::Time::singleton().get_ticks_msec() % 9999));
-        self.player_nickname = Some(nickname.clone());
-        
-        let url = format!("{}/rooms/{}/join", self.server_url, room_code);
-        let body = format!(r#"{{"nickname":"{}","avatar_id":0}}"#, nickname);
-        
-        godot_print!("Auto-joining as host: POST {} with body: {}", url, body);
-        
-        if let Some(mut request) = self.base_mut().try_get_node_as::<HttpRequest>("JoinRoomRequest") {
-            let mut headers = PackedStringArray::new();
-            headers.push("Content-Type: application/json");
-            
-            request.request_ex(&url)
-                .custom_headers(&headers)
-                .method(Method::POST)
-                .request_data(&body)
-                .done();
-        }
-    }
-    
     // Helper methods
     fn set_status(&mut self, message: &str, is_error: bool) {
         if let Some(mut label) = self.base_mut().try_get_node_as::<Label>("CenterContainer/VBoxContainer/StatusLabel") {
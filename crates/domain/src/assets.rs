@@ -54,65 +54,197 @@ pub const LOCATIONS: &[&str] = &[
     "in a library of floating books",
 ];
 
-pub const MODIFIERS: &[&str] = &[
-    "wearing a top hat",
-    "holding a lightsaber",
-    "wearing sunglasses",
-    "riding a skateboard",
-    "eating a pizza",
-    "on fire (safely)",
-    "covered in glitter",
-    "wearing a cape",
-    "holding a balloon",
-    "wearing clown shoes",
-    "surrounded by butterflies",
-    "holding a sign that says 'Help'",
-    "wearing a tutu",
-    "holding a rubber chicken",
-    "wearing a space helmet",
-    "that is giant",
-    "that is tiny",
-    "that is glowing green",
-    "that is invisible (mostly)",
-    "made of jelly",
+/// The kinds of change a turn offers. Each turn's options come from
+/// different categories, so a player never picks between near-identical
+/// changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModifierCategory {
+    /// Gives the object something to wear, hold or eat.
+    Add,
+    /// Changes what it is like: size, material, texture, mood.
+    Style,
+    /// Changes where it is or how it gets around.
+    Move,
+    /// Changes its color.
+    Color,
+}
+
+impl ModifierCategory {
+    /// Every category, in display order.
+    pub const ALL: [Self; 4] = [Self::Add, Self::Style, Self::Move, Self::Color];
+
+    /// Name used in content packs and by clients.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Add => "add",
+            Self::Style => "style",
+            Self::Move => "move",
+            Self::Color => "color",
+        }
+    }
+}
+
+/// A modification offered to the current player.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "OptionRepr")]
+pub struct ModifierOption {
+    pub category: ModifierCategory,
+
+    /// The modifier itself ("wearing a top hat").
+    pub text: String,
+}
+
+/// Options as saved: games saved before categories existed hold bare text.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OptionRepr {
+    Categorized { category: ModifierCategory, text: String },
+    Text(String),
+}
+
+impl From<OptionRepr> for ModifierOption {
+    fn from(repr: OptionRepr) -> Self {
+        match repr {
+            OptionRepr::Categorized { category, text } => Self { category, text },
+            OptionRepr::Text(text) => {
+                let category = MODIFIERS.iter()
+                    .find(|(_, modifier)| *modifier == text)
+                    .map_or(ModifierCategory::Style, |(category, _)| *category);
+                Self { category, text }
+            }
+        }
+    }
+}
+
+pub const MODIFIERS: &[(ModifierCategory, &str)] = &[
+    (ModifierCategory::Add, "wearing a top hat"),
+    (ModifierCategory::Add, "holding a lightsaber"),
+    (ModifierCategory::Add, "wearing sunglasses"),
+    (ModifierCategory::Add, "eating a pizza"),
+    (ModifierCategory::Add, "wearing a cape"),
+    (ModifierCategory::Add, "holding a balloon"),
+    (ModifierCategory::Add, "wearing clown shoes"),
+    (ModifierCategory::Add, "holding a sign that says 'Help'"),
+    (ModifierCategory::Add, "wearing a tutu"),
+    (ModifierCategory::Add, "holding a rubber chicken"),
+    (ModifierCategory::Add, "wearing a space helmet"),
+    (ModifierCategory::Style, "on fire (safely)"),
+    (ModifierCategory::Style, "covered in glitter"),
+    (ModifierCategory::Style, "surrounded by butterflies"),
+    (ModifierCategory::Style, "that is giant"),
+    (ModifierCategory::Style, "that is tiny"),
+    (ModifierCategory::Style, "that is invisible (mostly)"),
+    (ModifierCategory::Style, "made of jelly"),
+    (ModifierCategory::Move, "riding a skateboard"),
+    (ModifierCategory::Move, "riding a unicorn"),
+    (ModifierCategory::Move, "flying through the clouds"),
+    (ModifierCategory::Move, "in outer space"),
+    (ModifierCategory::Move, "under the sea"),
+    (ModifierCategory::Move, "inside a snow globe"),
+    (ModifierCategory::Color, "that is glowing green"),
+    (ModifierCategory::Color, "painted bright pink"),
+    (ModifierCategory::Color, "painted in rainbow stripes"),
+    (ModifierCategory::Color, "that is shiny gold"),
+    (ModifierCategory::Color, "that is jet black"),
 ];
 
+use crate::content::ContentPack;
 use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-/// Generate a random composite goal and individual starting objects for players.
-pub fn generate_game_assets(player_count: usize) -> (String, Vec<String>) {
-    let mut rng = rand::thread_rng();
-    
+/// Generate a random composite goal and individual starting objects for players,
+/// drawing from `pack` with `rng`.
+pub fn generate_game_assets(pack: &ContentPack, player_count: usize, rng: &mut impl Rng) -> (String, Vec<String>) {
     // Pick 2-3 random elements for the composite goal
-    let animal = ANIMALS.choose(&mut rng).unwrap_or(&"A mystery animal");
-    let object = OBJECTS.choose(&mut rng).unwrap_or(&"A mystery object");
-    let location = LOCATIONS.choose(&mut rng).unwrap_or(&"in a mystery place");
+    let mut pick = |list: &[String], fallback: &str| {
+        list.choose(rng).cloned().unwrap_or_else(|| fallback.to_string())
+    };
+    let animal = pick(&pack.animals, "A mystery animal");
+    let object = pick(&pack.objects, "A mystery object");
+    let location = pick(&pack.locations, "in a mystery place");
     
     // Create a more complex composite goal
     let communal_goal = format!("{} holding {} {}", animal, object, location);
     
     // Pick unique starting objects for each player
     // We want these to be simple (just one object or animal)
-    let mut all_options = [ANIMALS, OBJECTS].concat();
-    all_options.shuffle(&mut rng);
+    let mut all_options = [pack.animals.as_slice(), pack.objects.as_slice()].concat();
+    all_options.shuffle(rng);
     
     let player_objects = all_options.into_iter()
         .take(player_count)
-        .map(|s| s.to_string())
         .collect();
         
     (communal_goal, player_objects)
 }
 
-/// Generate 4 random modification options.
-pub fn generate_modification_options() -> Vec<String> {
-    let mut rng = rand::thread_rng();
-    let mut options: Vec<String> = MODIFIERS.iter().map(|s| s.to_string()).collect();
-    options.shuffle(&mut rng);
-    options.into_iter().take(4).collect()
+/// Generate `count` random modification options from `pack` with `rng`,
+/// each from a different category while categories last.
+pub fn generate_modification_options(pack: &ContentPack, count: usize, rng: &mut impl Rng) -> Vec<ModifierOption> {
+    let mut pools: Vec<Vec<ModifierOption>> = pack.modifiers.iter()
+        .filter(|(_, texts)| !texts.is_empty())
+        .map(|(category, texts)| {
+            let mut pool: Vec<ModifierOption> = texts.iter()
+                .map(|text| ModifierOption { category: *category, text: text.clone() })
+                .collect();
+            pool.shuffle(rng);
+            pool
+        })
+        .collect();
+    pools.shuffle(rng);
+
+    // One option per category in turn, until enough are drawn or all run out
+    let mut options = Vec::new();
+    while options.len() < count && pools.iter().any(|pool| !pool.is_empty()) {
+        for pool in pools.iter_mut() {
+            if options.len() == count {
+                break;
+            }
+            options.extend(pool.pop());
+        }
+    }
+    options
 }
 
-/// Apply a modification to an object description.
-pub fn apply_modification(object: &str, modifier: &str) -> String {
-    format!("{} {}", object, modifier)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_options_come_from_different_categories() {
+        let pack = ContentPack::builtin();
+        for seed in 0..50 {
+            let options = generate_modification_options(&pack, 4, &mut StdRng::seed_from_u64(seed));
+            let categories: HashSet<_> = options.iter().map(|option| option.category).collect();
+            assert_eq!(categories.len(), 4, "{:?}", options);
+        }
+    }
+
+    #[test]
+    fn test_extra_options_repeat_categories_without_repeating_text() {
+        let pack = ContentPack::builtin();
+        let options = generate_modification_options(&pack, 6, &mut StdRng::seed_from_u64(1));
+        let texts: HashSet<_> = options.iter().map(|option| option.text.as_str()).collect();
+        assert_eq!(texts.len(), 6);
+        let categories: HashSet<_> = options[..4].iter().map(|option| option.category).collect();
+        assert_eq!(categories.len(), 4);
+    }
+
+    #[test]
+    fn test_options_saved_as_text_get_a_category() {
+        let options: Vec<ModifierOption> = serde_json::from_str(
+            r#"["riding a skateboard", "made of bubbles", {"category": "color", "text": "painted bright pink"}]"#,
+        ).unwrap();
+        assert_eq!(options[0].category, ModifierCategory::Move);
+        assert_eq!(options[1].category, ModifierCategory::Style);
+        assert_eq!(options[2].category, ModifierCategory::Color);
+
+        let json = serde_json::to_string(&options[0]).unwrap();
+        assert_eq!(serde_json::from_str::<ModifierOption>(&json).unwrap(), options[0]);
+    }
 }
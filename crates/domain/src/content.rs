@@ -0,0 +1,268 @@
+//! Content packs: the animals, objects, locations and modifiers games are
+//! built from.
+//!
+//! Packs are plain JSON so prompts can change without a rebuild. The lists
+//! compiled into [`crate::assets`] ship as the built-in `default` pack.
+
+use crate::assets::{ModifierCategory, ANIMALS, LOCATIONS, MODIFIERS, OBJECTS};
+use crate::errors::ContentError;
+use crate::settings::{MAX_OPTIONS_PER_TURN, MAX_PLAYERS};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// ID of the pack built from the compiled-in lists.
+pub const DEFAULT_PACK_ID: &str = "default";
+
+/// A named, versioned set of game content.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentPack {
+    /// Short unique identifier (lowercase letters, digits, `-` and `_`).
+    pub id: String,
+
+    /// Name shown to hosts.
+    pub name: String,
+
+    /// Version of the pack, for telling edits apart.
+    pub version: String,
+
+    /// Animals for goals and starting objects.
+    pub animals: Vec<String>,
+
+    /// Objects for goals and starting objects.
+    pub objects: Vec<String>,
+
+    /// Where the goal takes place ("in outer space").
+    pub locations: Vec<String>,
+
+    /// Changes offered to players each turn ("wearing a top hat"), by
+    /// category.
+    pub modifiers: BTreeMap<ModifierCategory, Vec<String>>,
+}
+
+impl ContentPack {
+    /// The pack built from the compiled-in lists.
+    pub fn builtin() -> Self {
+        let list = |items: &[&str]| items.iter().map(|s| s.to_string()).collect();
+        Self {
+            id: DEFAULT_PACK_ID.to_string(),
+            name: "Cartoon Classics".to_string(),
+            version: "1.0.0".to_string(),
+            animals: list(ANIMALS),
+            objects: list(OBJECTS),
+            locations: list(LOCATIONS),
+            modifiers: ModifierCategory::ALL.iter()
+                .map(|category| {
+                    let texts = MODIFIERS.iter()
+                        .filter(|(c, _)| c == category)
+                        .map(|(_, text)| text.to_string())
+                        .collect();
+                    (*category, texts)
+                })
+                .collect(),
+        }
+    }
+
+    /// Parse and validate a pack from JSON.
+    pub fn from_json(json: &str) -> Result<Self, ContentError> {
+        let pack: Self = serde_json::from_str(json).map_err(|e| ContentError::Parse(e.to_string()))?;
+        pack.validate()?;
+        Ok(pack)
+    }
+
+    /// Check the pack can supply a full game.
+    ///
+    /// Every player needs a distinct starting object (animals and objects
+    /// together), every turn needs enough distinct modifiers, and no entry
+    /// may be blank or repeated within its list.
+    pub fn validate(&self) -> Result<(), ContentError> {
+        let invalid = |reason: String| ContentError::Invalid { pack: self.id.clone(), reason };
+
+        let id_chars = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_';
+        if self.id.is_empty() || !self.id.chars().all(id_chars) {
+            return Err(invalid("id must be lowercase letters, digits, '-' or '_'".to_string()));
+        }
+        if self.name.trim().is_empty() {
+            return Err(invalid("name must not be empty".to_string()));
+        }
+        if self.version.trim().is_empty() {
+            return Err(invalid("version must not be empty".to_string()));
+        }
+
+        // Every category is needed for a turn's options to differ in kind
+        for category in ModifierCategory::ALL {
+            if self.modifiers.get(&category).is_none_or(Vec::is_empty) {
+                return Err(invalid(format!("modifiers need at least one '{}' entry", category.as_str())));
+            }
+        }
+
+        let modifiers: Vec<String> = self.modifiers.values().flatten().cloned().collect();
+        let lists = [
+            ("animals", &self.animals, 1),
+            ("objects", &self.objects, 1),
+            ("locations", &self.locations, 1),
+            ("modifiers", &modifiers, MAX_OPTIONS_PER_TURN),
+        ];
+        for (name, list, min) in lists {
+            if list.len() < min {
+                return Err(invalid(format!("{} needs at least {} entries (got {})", name, min, list.len())));
+            }
+            if list.iter().any(|entry| entry.trim().is_empty()) {
+                return Err(invalid(format!("{} has a blank entry", name)));
+            }
+            let mut seen = HashSet::new();
+            if let Some(entry) = list.iter().find(|entry| !seen.insert(entry.as_str())) {
+                return Err(invalid(format!("{} lists '{}' twice", name, entry)));
+            }
+        }
+
+        let starting_objects = self.animals.len() + self.objects.len();
+        if starting_objects < MAX_PLAYERS {
+            return Err(invalid(format!(
+                "animals and objects together need at least {} entries for a full room (got {})",
+                MAX_PLAYERS, starting_objects
+            )));
+        }
+        Ok(())
+    }
+
+    /// Combine several decks into one pack, keeping the first copy of any
+    /// entry that appears in more than one (modifiers keep their category).
+    ///
+    /// The combined id (`spooky+sci-fi`) is not a loadable pack id.
+    pub fn combine(packs: &[&ContentPack]) -> ContentPack {
+        let join = |field: fn(&ContentPack) -> &str, sep: &str| {
+            packs.iter().map(|pack| field(pack)).collect::<Vec<_>>().join(sep)
+        };
+        let merge = |field: fn(&ContentPack) -> &Vec<String>| {
+            let mut seen = HashSet::new();
+            packs.iter()
+                .flat_map(|pack| field(pack))
+                .filter(|entry| seen.insert(entry.as_str()))
+                .cloned()
+                .collect()
+        };
+        let mut seen = HashSet::new();
+        let mut modifiers: BTreeMap<ModifierCategory, Vec<String>> = BTreeMap::new();
+        for pack in packs {
+            for (category, texts) in &pack.modifiers {
+                modifiers.entry(*category)
+                    .or_default()
+                    .extend(texts.iter().filter(|text| seen.insert(text.as_str())).cloned());
+            }
+        }
+        ContentPack {
+            id: join(|pack| &pack.id, "+"),
+            name: join(|pack| &pack.name, " + "),
+            version: join(|pack| &pack.version, "+"),
+            animals: merge(|pack| &pack.animals),
+            objects: merge(|pack| &pack.objects),
+            locations: merge(|pack| &pack.locations),
+            modifiers,
+        }
+    }
+}
+
+impl Default for ContentPack {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPOOKY_MODIFIERS: &str = r#""add": ["with fangs", "in a cape"], "style": ["dripping", "see-through"],
+        "move": ["in a graveyard"], "color": ["glowing"]"#;
+
+    fn pack_json(modifiers: &str) -> String {
+        format!(
+            r#"{{
+                "id": "spooky", "name": "Spooky", "version": "0.1.0",
+                "animals": ["A bat", "A black cat", "A raven", "A spider"],
+                "objects": ["A pumpkin", "A cauldron", "A candle", "A broom"],
+                "locations": ["in a haunted house"],
+                "modifiers": {{{}}}
+            }}"#,
+            modifiers
+        )
+    }
+
+    #[test]
+    fn test_builtin_pack_is_valid() {
+        let pack = ContentPack::builtin();
+        assert!(pack.validate().is_ok());
+        assert_eq!(pack.id, DEFAULT_PACK_ID);
+        assert_eq!(pack.animals.len(), ANIMALS.len());
+    }
+
+    #[test]
+    fn test_shipped_default_pack_matches_builtin() {
+        let shipped = include_str!("../../../content/default.json");
+        assert_eq!(ContentPack::from_json(shipped).unwrap(), ContentPack::builtin());
+    }
+
+    #[test]
+    fn test_shipped_decks_are_valid() {
+        let shipped = [
+            include_str!("../../../content/spooky.json"),
+            include_str!("../../../content/sci-fi.json"),
+            include_str!("../../../content/office-party.json"),
+        ];
+        for json in shipped {
+            let pack = ContentPack::from_json(json).unwrap();
+            assert_ne!(pack.id, DEFAULT_PACK_ID);
+        }
+    }
+
+    #[test]
+    fn test_pack_from_json() {
+        let pack = ContentPack::from_json(&pack_json(SPOOKY_MODIFIERS)).unwrap();
+        assert_eq!(pack.name, "Spooky");
+        assert_eq!(pack.modifiers[&ModifierCategory::Style], ["dripping", "see-through"]);
+        assert_eq!(pack.modifiers.values().flatten().count(), 6);
+    }
+
+    #[test]
+    fn test_combine_merges_lists_without_repeats() {
+        let json = pack_json(r#""add": ["with fangs", "in a cape"], "style": ["made of jelly", "see-through"],
+            "move": ["in a graveyard"], "color": ["glowing"]"#);
+        let spooky = ContentPack::from_json(&json).unwrap();
+        let builtin = ContentPack::builtin();
+
+        let combined = ContentPack::combine(&[&spooky, &builtin]);
+        assert_eq!(combined.id, "spooky+default");
+        assert_eq!(combined.name, "Spooky + Cartoon Classics");
+        assert_eq!(combined.animals.len(), spooky.animals.len() + builtin.animals.len());
+        let count = |pack: &ContentPack| pack.modifiers.values().flatten().count();
+        assert_eq!(count(&combined), count(&spooky) + count(&builtin) - 1);
+        assert_eq!(combined.modifiers[&ModifierCategory::Color][0], "glowing");
+
+        assert_eq!(ContentPack::combine(&[&spooky]), spooky);
+    }
+
+    #[test]
+    fn test_invalid_packs_explain_why() {
+        let err = ContentPack::from_json("{").unwrap_err();
+        assert!(matches!(err, ContentError::Parse(_)));
+
+        let json = pack_json(r#""add": ["a"], "style": ["b"], "move": ["c"], "color": ["d"]"#);
+        let err = ContentPack::from_json(&json).unwrap_err();
+        assert!(err.to_string().contains("modifiers needs at least"), "{}", err);
+
+        let json = pack_json(r#""add": ["a", "b", "c", "d", "e", "f"], "style": ["g"], "move": ["h"]"#);
+        let err = ContentPack::from_json(&json).unwrap_err();
+        assert!(err.to_string().contains("at least one 'color'"), "{}", err);
+
+        let json = pack_json(r#""add": ["a", "b"], "style": ["c"], "move": ["d", "e"], "color": ["a"]"#);
+        let err = ContentPack::from_json(&json).unwrap_err();
+        assert!(err.to_string().contains("'a' twice"), "{}", err);
+
+        let pack = ContentPack { id: "Bad Id".to_string(), ..ContentPack::builtin() };
+        assert!(pack.validate().is_err());
+
+        let pack = ContentPack { animals: vec!["A bat".to_string()], objects: vec!["A pumpkin".to_string()], ..ContentPack::builtin() };
+        let err = pack.validate().unwrap_err();
+        assert!(err.to_string().contains("full room"), "{}", err);
+    }
+}
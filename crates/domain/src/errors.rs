@@ -1,7 +1,7 @@
 //! Domain-specific errors for room and player management.
 
 use thiserror::Error;
-use crate::types::{PlayerId, RoomId};
+use crate::types::{PlayerId, RoomId, SpectatorId};
 
 /// Errors that can occur during room operations.
 #[derive(Debug, Error, serde::Serialize, serde::Deserialize)]
@@ -30,15 +30,33 @@ pub enum RoomError {
     #[error("Player not found")]
     PlayerNotFoundSimple,
     
+    #[error("Spectator {0} not found in room {1}")]
+    SpectatorNotFound(SpectatorId, RoomId),
+    
     #[error("Nickname '{0}' is already taken in room {1}")]
     NicknameTaken(String, RoomId),
     
-    #[error("Not enough players to start (need at least 2)")]
-    NotEnoughPlayers(RoomId),
+    #[error("No player named '{0}' in room {1}")]
+    NicknameNotFound(String, RoomId),
+    
+    #[error("Not enough players to start (need at least {1})")]
+    NotEnoughPlayers(RoomId, usize),
     
     #[error("Invalid room code: {0}")]
     InvalidCode(String),
     
+    #[error("Image generation failed: {0}")]
+    ImageGeneration(String),
+    
+    #[error("Invalid settings: {0}")]
+    InvalidSettings(String),
+    
+    #[error("Missing or invalid session token")]
+    Unauthorized,
+    
+    #[error("Not allowed: {0}")]
+    Forbidden(String),
+    
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -62,6 +80,39 @@ pub enum JoinError {
     InvalidNickname,
 }
 
+/// Errors that can occur while generating images.
+#[derive(Debug, Error, serde::Serialize, serde::Deserialize)]
+pub enum ImageError {
+    #[error("Image backend unavailable: {0}")]
+    Unavailable(String),
+    
+    #[error("Image generation failed: {0}")]
+    Failed(String),
+}
+
+/// Errors that can occur while loading a content pack.
+#[derive(Debug, Error, serde::Serialize, serde::Deserialize)]
+pub enum ContentError {
+    #[error("Content pack is not valid JSON: {0}")]
+    Parse(String),
+    
+    #[error("Content pack '{pack}' is invalid: {reason}")]
+    Invalid { pack: String, reason: String },
+    
+    #[error("Content pack '{0}' is defined more than once")]
+    DuplicatePack(String),
+}
+
+/// Errors that can occur while replaying a game log.
+#[derive(Debug, Error, serde::Serialize, serde::Deserialize)]
+pub enum ReplayError {
+    #[error("Game log must start with the room being created")]
+    MissingRoomCreated,
+    
+    #[error("Event {index} could not be replayed: {reason}")]
+    Rejected { index: usize, reason: String },
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -83,6 +134,9 @@ mod tests {
         let err = RoomError::NicknameTaken("Alice".to_string(), room_id);
         assert!(err.to_string().contains("Alice"));
         assert!(err.to_string().contains("taken"));
+        
+        let err = RoomError::NicknameNotFound("Alice".to_string(), room_id);
+        assert!(err.to_string().contains("No player named 'Alice'"));
     }
 
     #[test]
@@ -94,6 +148,10 @@ mod tests {
             RoomError::GameAlreadyStarted,
             RoomError::PlayerNotFoundSimple,
             RoomError::InvalidCode("INVALID".to_string()),
+            RoomError::ImageGeneration("backend offline".to_string()),
+            RoomError::InvalidSettings("rounds must be between 1 and 10 (got 0)".to_string()),
+            RoomError::Unauthorized,
+            RoomError::Forbidden("only players can vote".to_string()),
             RoomError::Internal("Something went wrong".to_string()),
         ];
         
@@ -134,6 +192,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_image_error_display() {
+        let err = ImageError::Unavailable("no GPU".to_string());
+        assert!(err.to_string().contains("no GPU"));
+        
+        let err = ImageError::Failed("timeout".to_string());
+        assert!(err.to_string().contains("timeout"));
+    }
+
+    #[test]
+    fn test_replay_error_display() {
+        let err = ReplayError::Rejected { index: 7, reason: "Not your turn".to_string() };
+        assert!(err.to_string().contains('7'));
+        assert!(err.to_string().contains("Not your turn"));
+    }
+
     #[test]
     fn test_error_variants_are_error_trait() {
         use std::error::Error;
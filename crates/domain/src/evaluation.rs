@@ -0,0 +1,157 @@
+//! How close the group got to the communal goal.
+//!
+//! The goal is broken into keywords ("penguin", "taco", "beach", ...) and
+//! compared with the words of every player's final object. The share of goal
+//! keywords the group produced decides the [`GameOutcome`].
+
+use crate::game::GameOutcome;
+use crate::types::ImageId;
+use serde::{Deserialize, Serialize};
+
+/// Share of goal keywords needed for [`GameOutcome::Success`].
+const SUCCESS_COVERAGE: f32 = 0.75;
+
+/// Share of goal keywords needed for [`GameOutcome::Close`].
+const CLOSE_COVERAGE: f32 = 0.4;
+
+/// Most missing keywords named in the explanation.
+const MISSING_IN_EXPLANATION: usize = 4;
+
+/// Words that carry no meaning of their own in goals and objects.
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "the", "and", "of", "in", "on", "at", "to", "for", "with", "into",
+    "inside", "under", "top", "holding", "that", "is", "made", "full", "says",
+];
+
+/// The group's result, shown in the Results stage.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameEvaluation {
+    /// How close the group got.
+    pub outcome: GameOutcome,
+
+    /// One or two sentences telling the group why.
+    pub explanation: String,
+
+    /// Goal keywords found in the final objects, in goal order.
+    pub matched: Vec<String>,
+
+    /// Goal keywords nobody produced, in goal order.
+    pub missing: Vec<String>,
+}
+
+/// Compare the final objects (and images) with the communal goal.
+pub fn evaluate<'a>(
+    goal: &str,
+    objects: impl IntoIterator<Item = &'a str>,
+    final_image: &ImageId,
+    goal_image: &ImageId,
+) -> GameEvaluation {
+    let produced: Vec<String> = objects.into_iter().flat_map(keywords).collect();
+    let (matched, missing): (Vec<String>, Vec<String>) = keywords(goal)
+        .into_iter()
+        .partition(|word| produced.contains(word));
+
+    if final_image == goal_image {
+        return GameEvaluation {
+            outcome: GameOutcome::Success,
+            explanation: "Spot on! The final picture is the goal picture.".to_string(),
+            matched,
+            missing,
+        };
+    }
+
+    let total = matched.len() + missing.len();
+    let coverage = if total == 0 { 0.0 } else { matched.len() as f32 / total as f32 };
+    let (outcome, verdict) = if coverage >= SUCCESS_COVERAGE {
+        (GameOutcome::Success, "Spot on!")
+    } else if coverage >= CLOSE_COVERAGE {
+        (GameOutcome::Close, "So close!")
+    } else {
+        (GameOutcome::Fail, "Not quite.")
+    };
+
+    let mut explanation = format!(
+        "{} Your creations matched {} of {} parts of the goal.",
+        verdict,
+        matched.len(),
+        total
+    );
+    if !missing.is_empty() {
+        let shown: Vec<&str> = missing.iter().take(MISSING_IN_EXPLANATION).map(String::as_str).collect();
+        let more = if missing.len() > shown.len() { ", ..." } else { "" };
+        explanation.push_str(&format!(" Missing: {}{}.", shown.join(", "), more));
+    }
+
+    GameEvaluation { outcome, explanation, matched, missing }
+}
+
+/// Distinct meaningful words of a description, lowercased and singular.
+fn keywords(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for word in text.split(|c: char| !c.is_alphanumeric()) {
+        let word = word.to_lowercase();
+        if word.is_empty() || STOP_WORDS.contains(&word.as_str()) {
+            continue;
+        }
+        let word = match word.strip_suffix('s') {
+            Some(stem) if stem.len() > 2 && !stem.ends_with('s') => stem.to_string(),
+            _ => word,
+        };
+        if !words.contains(&word) {
+            words.push(word);
+        }
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GOAL: &str = "A surfing giraffe holding A flying pizza slice on a tropical beach";
+
+    fn evaluate_objects(objects: &[&str]) -> GameEvaluation {
+        evaluate(GOAL, objects.iter().copied(), &ImageId::new("current"), &ImageId::new("goal"))
+    }
+
+    #[test]
+    fn test_keywords_skip_filler_and_plurals() {
+        assert_eq!(
+            keywords("A pair of sneakers with wings"),
+            vec!["pair", "sneaker", "wing"]
+        );
+        assert_eq!(keywords("A disco-dancing penguin"), vec!["disco", "dancing", "penguin"]);
+        assert_eq!(keywords("A glass"), vec!["glass"]);
+    }
+
+    #[test]
+    fn test_success_when_most_of_goal_present() {
+        let result = evaluate_objects(&[
+            "A surfing giraffe on a beach",
+            "A flying pizza slice that is tropical",
+        ]);
+        assert_eq!(result.outcome, GameOutcome::Success);
+        assert!(result.missing.is_empty());
+        assert!(result.explanation.starts_with("Spot on!"));
+    }
+
+    #[test]
+    fn test_close_and_fail() {
+        let close = evaluate_objects(&["A surfing giraffe", "A flying pizza"]);
+        assert_eq!(close.outcome, GameOutcome::Close);
+        assert_eq!(close.missing, vec!["slice", "tropical", "beach"]);
+        assert!(close.explanation.contains("Missing: slice, tropical, beach."));
+
+        let fail = evaluate_objects(&["A wizard cat wearing a cape"]);
+        assert_eq!(fail.outcome, GameOutcome::Fail);
+        assert!(fail.matched.is_empty());
+        assert!(fail.explanation.contains("0 of 7"));
+    }
+
+    #[test]
+    fn test_matching_image_is_success() {
+        let image = ImageId::new("same");
+        let result = evaluate(GOAL, ["A wizard cat"], &image, &image);
+        assert_eq!(result.outcome, GameOutcome::Success);
+    }
+}
@@ -0,0 +1,103 @@
+//! Change notifications emitted whenever the RoomManager mutates a room.
+//!
+//! Events are deltas: clients start from a full room snapshot and then apply
+//! events as they arrive. The RoomManager queues them and the server drains
+//! the queue after each mutation to push them to connected clients.
+
+use crate::assets::ModifierOption;
+use crate::game::{GameStage, PlayerAction};
+use crate::room::Participant;
+use crate::settings::GameSettings;
+use crate::types::{AvatarId, PlayerId, SpectatorId};
+use serde::{Deserialize, Serialize};
+
+/// A change to a room or its game.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RoomEvent {
+    /// A player joined the lobby.
+    PlayerJoined {
+        player_id: PlayerId,
+        nickname: String,
+        avatar_id: AvatarId,
+    },
+
+    /// A player left the room.
+    PlayerLeft { player_id: PlayerId },
+
+    /// An audience member started watching.
+    SpectatorJoined { spectator_id: SpectatorId, nickname: String },
+
+    /// An audience member stopped watching.
+    SpectatorLeft { spectator_id: SpectatorId },
+
+    /// The room went unused for too long; `RoomClosed` follows.
+    RoomExpired,
+
+    /// The room was removed and no further events will follow.
+    RoomClosed,
+
+    /// Host privileges passed to another participant.
+    HostChanged { host: Participant },
+
+    /// The display or a player lost or regained its connection.
+    ConnectionChanged { participant: Participant, connected: bool },
+
+    /// The host changed the game settings.
+    SettingsChanged { settings: GameSettings },
+
+    /// The game started and the goal reveal began.
+    GameStarted,
+
+    /// The game moved to a new stage.
+    StageChanged {
+        stage: GameStage,
+        /// When the new stage ends on its own (Unix seconds), if ever.
+        deadline: Option<u64>,
+    },
+
+    /// A player is ready to leave the goal reveal.
+    PlayerReady { player_id: PlayerId },
+
+    /// A new turn started, or the current one was held or restarted for a
+    /// disconnected player.
+    TurnStarted {
+        player_id: PlayerId,
+        round: u32,
+        options: Vec<ModifierOption>,
+        /// When the turn is skipped automatically (Unix seconds), if ever.
+        deadline: Option<u64>,
+    },
+
+    /// A player's action (or skipped turn) was applied.
+    ActionApplied { action: PlayerAction },
+
+    /// A player submitted their votes.
+    VoteReceived { voter_id: PlayerId },
+
+    /// An audience member submitted their votes.
+    AudienceVoteReceived { spectator_id: SpectatorId },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_are_tagged() {
+        let event = RoomEvent::PlayerLeft { player_id: PlayerId::new() };
+        let json = serde_json::to_value(&event).expect("Should serialize");
+        assert_eq!(json["type"], "player_left");
+
+        let json = serde_json::to_value(RoomEvent::RoomClosed).expect("Should serialize");
+        assert_eq!(json["type"], "room_closed");
+    }
+
+    #[test]
+    fn test_event_round_trip() {
+        let event = RoomEvent::StageChanged { stage: GameStage::Voting, deadline: None };
+        let json = serde_json::to_string(&event).expect("Should serialize");
+        let deserialized: RoomEvent = serde_json::from_str(&json).expect("Should deserialize");
+        assert_eq!(deserialized, event);
+    }
+}
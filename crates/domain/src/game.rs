@@ -1,9 +1,27 @@
 //! Game state and turn progression logic.
 
-use crate::types::{ImageId, OptionId, PlayerId};
+use crate::assets::{ModifierCategory, ModifierOption};
+use crate::content::ContentPack;
+use crate::evaluation::GameEvaluation;
+use crate::image_generator::{ImageGenerator, ImageRequest};
+use crate::object::{GameObject, Modification};
+use crate::rng::GameRng;
+use crate::settings::{DisconnectPolicy, GameSettings, MAX_WAIT_SECONDS};
+use crate::types::{ImageId, PlayerId, SpectatorId};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
+/// A transition the server made because a deadline passed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Expiry {
+    /// The goal reveal ended and turns began.
+    RevealEnded,
+    /// The given player's turn ran out and was skipped.
+    TurnSkipped(PlayerId),
+    /// Voting time ran out and results are shown.
+    VotingEnded,
+}
+
 /// The stage of the game.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum GameStage {
@@ -20,7 +38,7 @@ pub enum GameStage {
 /// The state of an active game.
 ///
 /// Tracks the goal image, current image, turn order, and action history.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GameState {
     /// The target image players are trying to reach.
     pub goal_image: ImageId,
@@ -50,6 +68,8 @@ pub struct GameState {
     pub current_turn_index: usize,
     
     /// Maximum number of rounds (each player acts once per round).
+    ///
+    /// Mirrors `settings.rounds` once settings are applied.
     pub max_rounds: u32,
     
     /// Current round number (0-based).
@@ -58,12 +78,20 @@ pub struct GameState {
     /// History of all actions taken.
     pub actions: Vec<PlayerAction>,
 
-    /// The 4 options available to the current player.
-    pub current_options: Vec<String>,
+    /// The 4 options available to the current player, each from a
+    /// different category.
+    pub current_options: Vec<ModifierOption>,
 
     /// Timestamp when the current turn started (Unix seconds).
     pub turn_start_time: Option<u64>,
 
+    /// Stage lengths and options per turn for this game.
+    #[serde(default)]
+    pub settings: GameSettings,
+
+    /// Players who have seen their starting object and are ready to play.
+    pub reveal_acknowledged: HashSet<PlayerId>,
+
     /// Votes received: Voter -> Target -> Stars (0-5).
     pub votes: HashMap<PlayerId, HashMap<PlayerId, u8>>,
 
@@ -72,6 +100,42 @@ pub struct GameState {
 
     /// Timestamp when the current stage started (Unix seconds).
     pub stage_start_time: u64,
+
+    /// How close the group got to the goal, computed on entering Results.
+    #[serde(default)]
+    pub evaluation: Option<GameEvaluation>,
+
+    /// Players currently disconnected (or gone from the room).
+    #[serde(default)]
+    pub disconnected: HashSet<PlayerId>,
+
+    /// Players who left the room; their turns are always skipped.
+    #[serde(default)]
+    pub left: HashSet<PlayerId>,
+
+    /// Players who joined during the current round, waiting for it to end
+    /// before taking turns.
+    #[serde(default)]
+    pub pending_players: Vec<PlayerId>,
+
+    /// Audience votes received: Spectator -> Target -> Stars (0-5).
+    ///
+    /// Kept apart from `votes`; they never decide when voting ends.
+    #[serde(default)]
+    pub audience_votes: HashMap<SpectatorId, HashMap<PlayerId, u8>>,
+
+    /// Content pack per-turn options and late joiners' objects are drawn from.
+    #[serde(default)]
+    pub content: ContentPack,
+
+    /// Seeded randomness every draw of the game goes through.
+    #[serde(default)]
+    pub rng: GameRng,
+
+    /// Each player's current object in parts; `player_current_objects`
+    /// holds its rendering.
+    #[serde(default)]
+    pub objects: HashMap<PlayerId, GameObject>,
 }
 
 impl GameState {
@@ -86,6 +150,9 @@ impl GameState {
     ) -> Self {
         let current_image = starting_image.clone();
         let player_current_objects = player_starting_objects.clone();
+        let objects = player_starting_objects.iter()
+            .map(|(id, object)| (*id, GameObject::new(object.clone())))
+            .collect();
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
@@ -106,12 +173,159 @@ impl GameState {
             actions: Vec::new(),
             current_options: Vec::new(),
             turn_start_time: None,
+            settings: GameSettings { rounds: max_rounds, ..GameSettings::default() },
+            reveal_acknowledged: HashSet::new(),
             votes: HashMap::new(),
             players_who_voted: HashSet::new(),
             stage_start_time: now,
+            evaluation: None,
+            disconnected: HashSet::new(),
+            left: HashSet::new(),
+            pending_players: Vec::new(),
+            audience_votes: HashMap::new(),
+            content: ContentPack::builtin(),
+            rng: GameRng::from_entropy(),
+            objects,
         }
     }
 
+    /// Apply the room's settings (builder style), including the round count.
+    pub fn with_settings(mut self, settings: GameSettings) -> Self {
+        self.max_rounds = settings.rounds;
+        self.settings = settings;
+        self
+    }
+
+    /// Draw per-turn options and late joiners' objects from `content`
+    /// (builder style).
+    pub fn with_content(mut self, content: ContentPack) -> Self {
+        self.content = content;
+        self
+    }
+
+    /// Continue drawing from `rng`, typically the one the goal and starting
+    /// objects were drawn with (builder style).
+    pub fn with_rng(mut self, rng: GameRng) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Timestamp (Unix seconds) when the current stage ends on its own.
+    ///
+    /// The reveal and voting end after their configured lengths; during turns
+    /// this is the turn deadline. Results have no deadline.
+    pub fn stage_deadline(&self) -> Option<u64> {
+        match self.stage {
+            GameStage::RevealGoal => Some(self.stage_start_time + self.settings.reveal_seconds),
+            GameStage::PlayerTurn => self.turn_deadline(),
+            GameStage::Voting => Some(self.stage_start_time + self.settings.voting_seconds),
+            GameStage::Results => None,
+        }
+    }
+
+    /// Check whether the current stage's deadline has passed at `now`.
+    pub fn is_stage_expired(&self, now: u64) -> bool {
+        self.stage_deadline().map(|deadline| now >= deadline).unwrap_or(false)
+    }
+
+    /// Record that a player is ready to leave the goal reveal.
+    ///
+    /// Once every player in `connected` has acknowledged, turns begin early.
+    /// Returns true if this acknowledgement ended the reveal.
+    pub fn acknowledge_reveal(&mut self, player_id: PlayerId, connected: &[PlayerId]) -> Result<bool, String> {
+        if self.stage != GameStage::RevealGoal {
+            return Err("Not in reveal stage".to_string());
+        }
+        if !self.players_in_order.contains(&player_id) {
+            return Err("Not a player in this game".to_string());
+        }
+        
+        self.reveal_acknowledged.insert(player_id);
+        
+        let everyone_ready = connected.iter().all(|id| self.reveal_acknowledged.contains(id));
+        if everyone_ready {
+            self.next_stage();
+        }
+        Ok(everyone_ready)
+    }
+
+    /// Timestamp (Unix seconds) when the current turn expires.
+    ///
+    /// Only present while a player's turn is in progress. While waiting for
+    /// a disconnected player under [`DisconnectPolicy::Wait`], the turn is
+    /// held for up to [`MAX_WAIT_SECONDS`] from the disconnect.
+    pub fn turn_deadline(&self) -> Option<u64> {
+        if self.stage != GameStage::PlayerTurn {
+            return None;
+        }
+        let seconds = if self.is_waiting_for_current_player() { MAX_WAIT_SECONDS } else { self.settings.turn_seconds };
+        self.turn_start_time.map(|start| start + seconds)
+    }
+
+    /// Update which players are connected, applying the disconnect policy.
+    ///
+    /// Under [`DisconnectPolicy::Skip`] a disconnected current player's turn
+    /// is skipped (as long as anyone is still connected). Under
+    /// [`DisconnectPolicy::Wait`] their turn is held and starts afresh when
+    /// they return. Either way, returning players take their next turn in
+    /// normal order, and players who left are always skipped.
+    pub fn set_connected_players(&mut self, connected: &[PlayerId]) {
+        let was_waiting = self.is_waiting_for_current_player();
+        self.disconnected = self.players_in_order.iter()
+            .chain(&self.pending_players)
+            .filter(|id| !connected.contains(id))
+            .copied()
+            .collect();
+        
+        if self.stage == GameStage::Voting {
+            self.end_voting_if_complete();
+        }
+        if self.stage != GameStage::PlayerTurn {
+            return;
+        }
+        if self.should_skip_current_player() {
+            self.skip_current_turn();
+        } else if !was_waiting && self.is_waiting_for_current_player() {
+            // The wait counts from the disconnect
+            self.turn_start_time = Some(std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs());
+        } else if was_waiting && !self.is_waiting_for_current_player() {
+            self.start_turn();
+        }
+    }
+
+    /// Note that a player left the room for good.
+    ///
+    /// Their remaining turns are skipped whatever the disconnect policy;
+    /// the next [`Self::set_connected_players`] skips one in progress.
+    pub fn remove_player(&mut self, player_id: PlayerId) {
+        self.left.insert(player_id);
+        self.pending_players.retain(|id| *id != player_id);
+    }
+
+    /// Check whether the current player is disconnected and being waited for.
+    fn is_waiting_for_current_player(&self) -> bool {
+        self.settings.disconnect_policy == DisconnectPolicy::Wait
+            && self.current_player().is_some_and(|id| self.disconnected.contains(&id) && !self.left.contains(&id))
+    }
+
+    /// Check whether the current player's turn should be skipped right away.
+    fn should_skip_current_player(&self) -> bool {
+        let skips = |id: &PlayerId| {
+            self.left.contains(id)
+                || (self.settings.disconnect_policy == DisconnectPolicy::Skip && self.disconnected.contains(id))
+        };
+        self.current_player().is_some_and(|id| skips(&id))
+            && self.players_in_order.iter().any(|id| !self.disconnected.contains(id))
+    }
+
+    /// Check whether the current turn has run out at `now` (Unix seconds).
+    pub fn is_turn_expired(&self, now: u64) -> bool {
+        self.turn_deadline().map(|deadline| now >= deadline).unwrap_or(false)
+    }
+
     /// Transition to the next stage.
     pub fn next_stage(&mut self) {
         let now = std::time::SystemTime::now()
@@ -124,9 +338,15 @@ impl GameState {
             GameStage::RevealGoal => {
                 self.stage = GameStage::PlayerTurn;
                 self.start_turn();
+                if self.should_skip_current_player() {
+                    self.skip_current_turn();
+                }
             },
-            GameStage::PlayerTurn => self.stage = GameStage::Voting,
-            GameStage::Voting => self.stage = GameStage::Results,
+            GameStage::PlayerTurn => {
+                self.seat_pending_players();
+                self.stage = GameStage::Voting;
+            }
+            GameStage::Voting => self.show_results(),
             GameStage::Results => {}
         }
     }
@@ -139,7 +359,8 @@ impl GameState {
     /// Start the turn for the current player.
     pub fn start_turn(&mut self) {
         if let Some(_) = self.current_player() {
-            self.current_options = crate::assets::generate_modification_options();
+            let mut rng = self.rng.next_draw();
+            self.current_options = crate::assets::generate_modification_options(&self.content, self.settings.options_per_turn, &mut rng);
             self.turn_start_time = Some(std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
@@ -148,7 +369,15 @@ impl GameState {
     }
 
     /// Submit an action for the current player.
-    pub fn submit_action(&mut self, player_id: PlayerId, option_index: Option<usize>) -> Result<(), String> {
+    ///
+    /// A chosen option asks `images` for a transformed image, which becomes the
+    /// new `current_image`. Skips (`None`) keep the current image.
+    pub fn submit_action(
+        &mut self,
+        player_id: PlayerId,
+        option_index: Option<usize>,
+        images: &dyn ImageGenerator,
+    ) -> Result<(), String> {
         if self.stage != GameStage::PlayerTurn {
             return Err("Not in turn stage".to_string());
         }
@@ -161,53 +390,160 @@ impl GameState {
             if idx >= self.current_options.len() {
                 return Err("Invalid option".to_string());
             }
-            let modifier = &self.current_options[idx];
+            let option = self.current_options[idx].clone();
+            let modifier = option.text.clone();
             
-            if let Some(obj) = self.player_current_objects.get_mut(&player_id) {
-                *obj = crate::assets::apply_modification(obj, modifier);
+            if let Some(current) = self.player_current_objects.get(&player_id) {
+                // Games saved before objects were structured start from their description
+                let mut object = self.objects.get(&player_id)
+                    .cloned()
+                    .unwrap_or_else(|| GameObject::new(current.clone()));
+                object.apply(&Modification::of(&option));
+                let resulting_object = object.to_string();
                 
+                // Generate before mutating so a failed backend leaves the turn untouched
+                let objects = self.objects_in_order_with(player_id, &object);
+                let request = ImageRequest::Transformation {
+                    goal: self.communal_goal.clone(),
+                    previous: self.current_image.clone(),
+                    objects,
+                    modifier: modifier.clone(),
+                };
+                let resulting_image = images.generate(&request).map_err(|e| e.to_string())?;
+                
+                self.objects.insert(player_id, object);
+                self.player_current_objects.insert(player_id, resulting_object.clone());
+                self.current_image = resulting_image.clone();
                 self.actions.push(PlayerAction {
                     player_id,
                     round: self.current_round,
                     option_chosen: Some(idx),
-                    modification: modifier.clone(),
-                    resulting_object: obj.clone(),
+                    category: Some(option.category),
+                    modification: modifier,
+                    resulting_object,
+                    resulting_image,
                 });
             }
         } else {
-             // Timeout or skip
-             self.actions.push(PlayerAction {
-                player_id,
-                round: self.current_round,
-                option_chosen: None,
-                modification: "No action".to_string(),
-                resulting_object: self.player_current_objects.get(&player_id).cloned().unwrap_or_default(),
-            });
+            // Timeout or skip
+            self.record_no_action(player_id);
         }
 
         self.advance_turn();
         Ok(())
     }
 
-    /// Advance to the next player's turn.
+    /// Skip the current player's turn, recording "No action".
+    fn skip_current_turn(&mut self) {
+        if let Some(player_id) = self.current_player() {
+            self.record_no_action(player_id);
+            self.advance_turn();
+        }
+    }
+
+    fn record_no_action(&mut self, player_id: PlayerId) {
+        self.actions.push(PlayerAction {
+            player_id,
+            round: self.current_round,
+            option_chosen: None,
+            category: None,
+            modification: "No action".to_string(),
+            resulting_object: self.player_current_objects.get(&player_id).cloned().unwrap_or_default(),
+            resulting_image: self.current_image.clone(),
+        });
+    }
+
+    /// Current objects in turn order, with `player_id`'s object replaced by `object`.
+    fn objects_in_order_with(&self, player_id: PlayerId, object: &GameObject) -> Vec<GameObject> {
+        self.players_in_order
+            .iter()
+            .filter_map(|id| {
+                if *id == player_id {
+                    Some(object.clone())
+                } else {
+                    // Games saved before objects were structured only hold descriptions
+                    self.objects.get(id).cloned()
+                        .or_else(|| self.player_current_objects.get(id).map(|o| GameObject::new(o.clone())))
+                }
+            })
+            .collect()
+    }
+
+    /// Advance to the next player's turn, skipping disconnected players
+    /// under [`DisconnectPolicy::Skip`].
     fn advance_turn(&mut self) {
-        self.current_turn_index += 1;
-        
-        // If we've gone through all players, start a new round
-        if self.current_turn_index >= self.players_in_order.len() {
-            self.current_turn_index = 0;
-            self.current_round += 1;
+        loop {
+            self.current_turn_index += 1;
+            
+            // If we've gone through all players, start a new round
+            if self.current_turn_index >= self.players_in_order.len() {
+                self.seat_pending_players();
+                self.current_turn_index = 0;
+                self.current_round += 1;
+            }
+
+            if self.current_round >= self.max_rounds {
+                self.stage = GameStage::Voting;
+                self.stage_start_time = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs();
+                return;
+            }
+            if !self.should_skip_current_player() {
+                break;
+            }
+            let player_id = self.current_player().expect("Turn index is in range");
+            self.record_no_action(player_id);
         }
+        self.start_turn();
+    }
 
-        if self.current_round >= self.max_rounds {
-            self.stage = GameStage::Voting;
-            self.stage_start_time = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs();
-        } else {
-            self.start_turn();
+    /// Check whether a new player may join the game now.
+    ///
+    /// Requires [`GameSettings::allow_late_join`] and a round of turns in
+    /// progress.
+    pub fn accepts_late_joins(&self) -> bool {
+        self.settings.allow_late_join && self.stage == GameStage::PlayerTurn
+    }
+
+    /// Add a player who joined mid-game with their starting object.
+    ///
+    /// They are appended to the turn order when the current round ends (or
+    /// when turns end early), and vote like everyone else.
+    pub fn add_late_player(&mut self, player_id: PlayerId, starting_object: String) -> Result<(), String> {
+        if !self.accepts_late_joins() {
+            return Err("Game is not accepting new players".to_string());
+        }
+        if self.player_starting_objects.contains_key(&player_id) {
+            return Err("Already a player in this game".to_string());
+        }
+        
+        self.player_starting_objects.insert(player_id, starting_object.clone());
+        self.objects.insert(player_id, GameObject::new(starting_object.clone()));
+        self.player_current_objects.insert(player_id, starting_object);
+        self.pending_players.push(player_id);
+        Ok(())
+    }
+
+    /// Draw a starting object for a late joiner that no player holds yet.
+    ///
+    /// Deals starting objects the way a new game does and takes the first
+    /// free one. With none free, returns `None` without using up a draw.
+    pub fn draw_late_object(&mut self) -> Option<String> {
+        let taken: HashSet<&String> = self.player_starting_objects.values().collect();
+        let pool: Vec<&String> = self.content.animals.iter().chain(&self.content.objects).collect();
+        if pool.iter().all(|object| taken.contains(object)) {
+            return None;
         }
+        let mut rng = self.rng.next_draw();
+        let (_, objects) = crate::assets::generate_game_assets(&self.content, pool.len(), &mut rng);
+        objects.into_iter().find(|object| !taken.contains(object))
+    }
+
+    /// Append players who joined during the round to the turn order.
+    fn seat_pending_players(&mut self) {
+        self.players_in_order.append(&mut self.pending_players);
     }
 
     /// Check if the game has reached its maximum rounds.
@@ -231,44 +567,118 @@ impl GameState {
             return Err("Not in voting stage".to_string());
         }
         
-        // Validate votes
-        for (target_id, stars) in &votes {
-            if *target_id == voter_id {
-                return Err("Cannot vote for yourself".to_string());
-            }
-            if *stars > 5 {
-                return Err("Stars must be between 0 and 5".to_string());
-            }
+        if !self.players_in_order.contains(&voter_id) {
+            return Err("Not a player in this game".to_string());
         }
+        
+        if votes.contains_key(&voter_id) {
+            return Err("Cannot vote for yourself".to_string());
+        }
+        self.validate_votes(&votes)?;
 
         // Store votes
         self.votes.insert(voter_id, votes);
         self.players_who_voted.insert(voter_id);
 
-        // Check if all players have voted
-        // Note: We only expect votes from connected players, but for simplicity we check against all players in order
-        // In a real scenario, we might want to handle disconnected players better.
-        if self.players_who_voted.len() >= self.players_in_order.len() {
-            self.stage = GameStage::Results;
-            self.stage_start_time = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs();
+        self.end_voting_if_complete();
+        Ok(())
+    }
+
+    /// Submit an audience member's votes for any of the players.
+    ///
+    /// Spectators may vote or not; either way voting ends as it would
+    /// without them. Voting again replaces their earlier votes.
+    pub fn submit_audience_votes(&mut self, spectator_id: SpectatorId, votes: HashMap<PlayerId, u8>) -> Result<(), String> {
+        if self.stage != GameStage::Voting {
+            return Err("Not in voting stage".to_string());
         }
-            
+        self.validate_votes(&votes)?;
+        self.audience_votes.insert(spectator_id, votes);
         Ok(())
     }
 
-    /// Calculate scores for all players.
-    pub fn calculate_scores(&self) -> HashMap<PlayerId, f32> {
+    /// Check that votes only rate players in this game, with 0-5 stars.
+    fn validate_votes(&self, votes: &HashMap<PlayerId, u8>) -> Result<(), String> {
+        for (target_id, stars) in votes {
+            if !self.players_in_order.contains(target_id) {
+                return Err("Can only vote for players in this game".to_string());
+            }
+            if *stars > 5 {
+                return Err("Stars must be between 0 and 5".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Players expected to vote: everyone still connected.
+    pub fn eligible_voters(&self) -> Vec<PlayerId> {
+        self.players_in_order.iter()
+            .filter(|id| !self.disconnected.contains(id))
+            .copied()
+            .collect()
+    }
+
+    /// Show results early once every eligible voter has voted.
+    ///
+    /// With nobody connected, voting waits for its deadline instead.
+    fn end_voting_if_complete(&mut self) {
+        if self.stage != GameStage::Voting {
+            return;
+        }
+        let eligible = self.eligible_voters();
+        if eligible.is_empty() || !eligible.iter().all(|id| self.players_who_voted.contains(id)) {
+            return;
+        }
+        self.stage_start_time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        self.show_results();
+    }
+
+    /// Enter the Results stage and evaluate how close the group got.
+    fn show_results(&mut self) {
+        self.stage = GameStage::Results;
+        self.evaluation = Some(self.evaluate());
+    }
+
+    /// Every image this game has shown.
+    pub fn images(&self) -> impl Iterator<Item = &ImageId> {
+        [&self.goal_image, &self.starting_image, &self.current_image].into_iter()
+            .chain(self.actions.iter().map(|action| &action.resulting_image))
+    }
+
+    /// Compare the final objects and image with the communal goal.
+    pub fn evaluate(&self) -> GameEvaluation {
+        let objects = self.players_in_order
+            .iter()
+            .filter_map(|id| self.player_current_objects.get(id))
+            .map(String::as_str);
+        crate::evaluation::evaluate(&self.communal_goal, objects, &self.current_image, &self.goal_image)
+    }
+
+    /// Calculate scores for all players, with the audience's apart.
+    ///
+    /// A score is the average of the stars a player actually received, so
+    /// players who didn't vote count neither as zero stars nor against anyone.
+    /// Players nobody rated get no score rather than zero.
+    pub fn calculate_scores(&self) -> Scores {
+        Scores {
+            players: self.average_stars(self.votes.values()),
+            audience: self.average_stars(self.audience_votes.values()),
+        }
+    }
+
+    /// Average each player's stars over the ballots that rated them.
+    fn average_stars<'a>(&self, ballots: impl Iterator<Item = &'a HashMap<PlayerId, u8>> + Clone) -> HashMap<PlayerId, f32> {
         let mut scores = HashMap::new();
         
         for player_id in &self.players_in_order {
             let mut total_stars = 0;
             let mut vote_count = 0;
             
-            for voter_votes in self.votes.values() {
-                if let Some(&stars) = voter_votes.get(player_id) {
+            for ballot in ballots.clone() {
+                if let Some(&stars) = ballot.get(player_id) {
                     total_stars += stars as u32;
                     vote_count += 1;
                 }
@@ -276,8 +686,6 @@ impl GameState {
             
             if vote_count > 0 {
                 scores.insert(*player_id, total_stars as f32 / vote_count as f32);
-            } else {
-                scores.insert(*player_id, 0.0);
             }
         }
         
@@ -285,8 +693,18 @@ impl GameState {
     }
 }
 
+/// Average stars per player, from the players' and the audience's votes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Scores {
+    /// Scores from the other players' votes.
+    pub players: HashMap<PlayerId, f32>,
+
+    /// Scores from spectators' votes, reported separately.
+    pub audience: HashMap<PlayerId, f32>,
+}
+
 /// A single player action during the game.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PlayerAction {
     /// The player who took this action.
     pub player_id: PlayerId,
@@ -296,12 +714,19 @@ pub struct PlayerAction {
     
     /// The option chosen by the player (index).
     pub option_chosen: Option<usize>,
+
+    /// Category of the chosen option; `None` for turns without an action.
+    #[serde(default)]
+    pub category: Option<ModifierCategory>,
     
     /// Text description of the modification.
     pub modification: String,
     
     /// The resulting object description.
     pub resulting_object: String,
+    
+    /// The image after this action (unchanged for skipped turns).
+    pub resulting_image: ImageId,
 }
 
 /// The outcome of a game evaluation.
@@ -320,6 +745,7 @@ pub enum GameOutcome {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::OptionId;
 
     #[test]
     fn test_game_state_creation() {
@@ -640,4 +1066,427 @@ mod tests {
         assert!(game.is_finished());
         assert_eq!(game.actions.len(), 6); // 3 rounds * 2 players
     }
+
+    #[derive(Debug)]
+    struct OfflineImages;
+
+    impl ImageGenerator for OfflineImages {
+        fn generate(&self, _request: &ImageRequest) -> Result<ImageId, crate::errors::ImageError> {
+            Err(crate::errors::ImageError::Unavailable("offline".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_failed_image_leaves_turn_untouched() {
+        let players = vec![PlayerId::new(), PlayerId::new()];
+        let mut objects = HashMap::new();
+        objects.insert(players[0], "A wizard cat".to_string());
+        objects.insert(players[1], "A sentient toaster".to_string());
+        let mut game = GameState::new(
+            ImageId::new("goal"),
+            "A test goal".to_string(),
+            ImageId::new("start"),
+            objects,
+            players.clone(),
+            1,
+        );
+        game.next_stage();
+        
+        let result = game.submit_action(players[0], Some(0), &OfflineImages);
+        
+        assert!(result.is_err());
+        assert!(game.actions.is_empty());
+        assert_eq!(game.current_player(), Some(players[0]));
+        assert_eq!(game.player_current_objects[&players[0]], "A wizard cat");
+        assert_eq!(game.current_image.as_str(), "start");
+    }
+
+    #[test]
+    fn test_transformation_request_uses_turn_order() {
+        use crate::image_generator::PlaceholderImageGenerator;
+
+        let players = vec![PlayerId::new(), PlayerId::new()];
+        let mut objects = HashMap::new();
+        objects.insert(players[0], "A wizard cat".to_string());
+        objects.insert(players[1], "A sentient toaster".to_string());
+        let mut game = GameState::new(
+            ImageId::new("goal"),
+            "A test goal".to_string(),
+            ImageId::new("start"),
+            objects,
+            players.clone(),
+            1,
+        );
+        game.next_stage();
+        let option = game.current_options[0].clone();
+        
+        game.submit_action(players[0], Some(0), &PlaceholderImageGenerator).unwrap();
+        assert_eq!(game.actions[0].category, Some(option.category));
+        
+        let mut cat = GameObject::new("A wizard cat");
+        cat.apply(&Modification::of(&option));
+        assert_eq!(game.player_current_objects[&players[0]], cat.to_string());
+        assert_eq!(game.objects[&players[0]], cat);
+        let expected = PlaceholderImageGenerator.generate(&ImageRequest::Transformation {
+            goal: "A test goal".to_string(),
+            previous: ImageId::new("start"),
+            objects: vec![
+                cat.clone(),
+                GameObject::new("A sentient toaster"),
+            ],
+            modifier: option.text,
+        }).unwrap();
+        assert_eq!(game.current_image, expected);
+    }
+
+    #[test]
+    fn test_turn_deadline() {
+        let players = vec![PlayerId::new(), PlayerId::new()];
+        let mut game = GameState::new(
+            ImageId::new("goal"),
+            "A test goal".to_string(),
+            ImageId::new("start"),
+            HashMap::new(),
+            players,
+            1,
+        )
+        .with_settings(GameSettings { rounds: 1, turn_seconds: 15, ..GameSettings::default() });
+        
+        // No deadline until turns begin
+        assert_eq!(game.turn_deadline(), None);
+        assert!(!game.is_turn_expired(u64::MAX));
+        
+        game.next_stage();
+        let start = game.turn_start_time.unwrap();
+        assert_eq!(game.turn_deadline(), Some(start + 15));
+        assert!(!game.is_turn_expired(start + 14));
+        assert!(game.is_turn_expired(start + 15));
+    }
+
+    fn reveal_game(players: &[PlayerId]) -> GameState {
+        GameState::new(
+            ImageId::new("goal"),
+            "A test goal".to_string(),
+            ImageId::new("start"),
+            HashMap::new(),
+            players.to_vec(),
+            1,
+        )
+        .with_settings(GameSettings { rounds: 1, reveal_seconds: 20, ..GameSettings::default() })
+    }
+
+    #[test]
+    fn test_reveal_stage_deadline() {
+        let players = vec![PlayerId::new(), PlayerId::new()];
+        let game = reveal_game(&players);
+        
+        assert_eq!(game.stage_deadline(), Some(game.stage_start_time + 20));
+        assert!(!game.is_stage_expired(game.stage_start_time + 19));
+        assert!(game.is_stage_expired(game.stage_start_time + 20));
+    }
+
+    #[test]
+    fn test_reveal_ends_when_all_connected_acknowledge() {
+        let players = vec![PlayerId::new(), PlayerId::new(), PlayerId::new()];
+        let mut game = reveal_game(&players);
+        
+        // Third player is disconnected and must not block the others
+        let connected = &players[..2];
+        assert_eq!(game.acknowledge_reveal(players[0], connected), Ok(false));
+        assert_eq!(game.stage, GameStage::RevealGoal);
+        
+        assert_eq!(game.acknowledge_reveal(players[1], connected), Ok(true));
+        assert_eq!(game.stage, GameStage::PlayerTurn);
+        assert!(game.turn_start_time.is_some());
+    }
+
+    #[test]
+    fn test_acknowledge_reveal_validation() {
+        let players = vec![PlayerId::new(), PlayerId::new()];
+        let mut game = reveal_game(&players);
+        
+        assert!(game.acknowledge_reveal(PlayerId::new(), &players).is_err());
+        
+        game.next_stage();
+        assert!(game.acknowledge_reveal(players[0], &players).is_err());
+    }
+
+    #[test]
+    fn test_voting_deadline() {
+        let players = vec![PlayerId::new(), PlayerId::new()];
+        let mut game = reveal_game(&players);
+        game.next_stage();
+        game.next_stage();
+        
+        assert_eq!(game.stage, GameStage::Voting);
+        let deadline = game.stage_start_time + game.settings.voting_seconds;
+        assert_eq!(game.stage_deadline(), Some(deadline));
+        
+        game.next_stage();
+        assert_eq!(game.stage_deadline(), None, "Results never end on their own");
+    }
+
+    #[test]
+    fn test_results_include_evaluation() {
+        let players = vec![PlayerId::new(), PlayerId::new()];
+        let mut game = reveal_game(&players);
+        game.communal_goal = "A wizard cat holding A golden banana trophy".to_string();
+        game.player_current_objects.insert(players[0], "A wizard cat".to_string());
+        game.player_current_objects.insert(players[1], "A golden banana trophy".to_string());
+        game.next_stage();
+        game.next_stage();
+        assert_eq!(game.evaluation, None);
+        
+        game.submit_votes(players[0], HashMap::new()).unwrap();
+        game.submit_votes(players[1], HashMap::new()).unwrap();
+        assert_eq!(game.stage, GameStage::Results);
+        let evaluation = game.evaluation.as_ref().expect("Results should be evaluated");
+        assert_eq!(evaluation.outcome, GameOutcome::Success);
+        assert_eq!(evaluation.matched, vec!["wizard", "cat", "golden", "banana", "trophy"]);
+    }
+
+    #[test]
+    fn test_settings_control_rounds_and_options() {
+        let players = vec![PlayerId::new(), PlayerId::new()];
+        let settings = GameSettings { rounds: 2, options_per_turn: 3, ..GameSettings::default() };
+        let mut game = reveal_game(&players).with_settings(settings);
+        assert_eq!(game.max_rounds, 2);
+        
+        game.next_stage();
+        assert_eq!(game.current_options.len(), 3);
+        
+        for _ in 0..4 {
+            let player = game.current_player().unwrap();
+            game.submit_action(player, None, &crate::image_generator::PlaceholderImageGenerator).unwrap();
+        }
+        assert_eq!(game.stage, GameStage::Voting);
+    }
+
+    #[test]
+    fn test_disconnected_players_turns_are_skipped() {
+        let players = vec![PlayerId::new(), PlayerId::new(), PlayerId::new()];
+        let mut game = reveal_game(&players).with_settings(GameSettings::default());
+        game.next_stage();
+        assert_eq!(game.current_player(), Some(players[0]));
+        
+        // The current player drops: their turn is skipped at once
+        game.set_connected_players(&players[1..]);
+        assert_eq!(game.current_player(), Some(players[1]));
+        assert_eq!(game.actions[0].player_id, players[0]);
+        assert_eq!(game.actions[0].modification, "No action");
+        
+        // Later turns skip them too, until they return
+        let images = crate::image_generator::PlaceholderImageGenerator;
+        game.submit_action(players[1], None, &images).unwrap();
+        game.submit_action(players[2], None, &images).unwrap();
+        assert_eq!(game.current_player(), Some(players[1]));
+        assert_eq!(game.actions.len(), 4);
+        
+        game.set_connected_players(&players);
+        game.submit_action(players[1], None, &images).unwrap();
+        game.submit_action(players[2], None, &images).unwrap();
+        assert_eq!(game.current_player(), Some(players[0]));
+    }
+
+    #[test]
+    fn test_nobody_connected_stops_skipping() {
+        let players = vec![PlayerId::new(), PlayerId::new()];
+        let mut game = reveal_game(&players);
+        game.next_stage();
+        
+        game.set_connected_players(&[]);
+        assert_eq!(game.current_player(), Some(players[0]));
+        assert!(game.actions.is_empty());
+        
+        // Once anyone is back, the absent current player is skipped
+        game.set_connected_players(&players[1..]);
+        assert_eq!(game.current_player(), Some(players[1]));
+    }
+
+    #[test]
+    fn test_wait_policy_holds_turn() {
+        let players = vec![PlayerId::new(), PlayerId::new()];
+        let settings = GameSettings { disconnect_policy: DisconnectPolicy::Wait, ..GameSettings::default() };
+        let mut game = reveal_game(&players).with_settings(settings);
+        game.next_stage();
+        
+        game.turn_start_time = Some(0);
+        game.set_connected_players(&players[1..]);
+        assert_eq!(game.current_player(), Some(players[0]));
+        
+        // Held from the disconnect, but not forever
+        let held = game.turn_start_time.unwrap();
+        assert!(held > 0);
+        assert_eq!(game.turn_deadline(), Some(held + MAX_WAIT_SECONDS));
+        assert!(!game.is_turn_expired(held + game.settings.turn_seconds));
+        assert!(game.is_turn_expired(held + MAX_WAIT_SECONDS));
+        
+        // The turn starts afresh when they return
+        game.turn_start_time = Some(0);
+        game.set_connected_players(&players);
+        assert_eq!(game.current_player(), Some(players[0]));
+        assert!(game.turn_start_time.unwrap() > 0);
+        assert!(game.turn_deadline().is_some());
+    }
+
+    #[test]
+    fn test_players_who_left_are_skipped_under_wait() {
+        let players = vec![PlayerId::new(), PlayerId::new(), PlayerId::new()];
+        let settings = GameSettings { disconnect_policy: DisconnectPolicy::Wait, ..GameSettings::default() };
+        let mut game = reveal_game(&players).with_settings(settings);
+        game.next_stage();
+        
+        game.remove_player(players[0]);
+        game.set_connected_players(&players[1..]);
+        assert_eq!(game.current_player(), Some(players[1]));
+        assert_eq!(game.actions[0].modification, "No action");
+        
+        // Their later turns are skipped too
+        let images = crate::image_generator::PlaceholderImageGenerator;
+        game.submit_action(players[1], None, &images).unwrap();
+        game.submit_action(players[2], None, &images).unwrap();
+        assert_eq!(game.current_player(), Some(players[1]));
+        assert_eq!(game.current_round, 1);
+    }
+
+    fn voting_game(players: &[PlayerId]) -> GameState {
+        let mut game = reveal_game(players);
+        game.stage = GameStage::Voting;
+        game
+    }
+
+    #[test]
+    fn test_voting_ends_when_connected_players_voted() {
+        let players = vec![PlayerId::new(), PlayerId::new(), PlayerId::new()];
+        let mut game = voting_game(&players);
+        game.set_connected_players(&players[..2]);
+        assert_eq!(game.eligible_voters(), players[..2].to_vec());
+        
+        game.submit_votes(players[0], HashMap::from([(players[1], 4)])).unwrap();
+        assert_eq!(game.stage, GameStage::Voting);
+        game.submit_votes(players[1], HashMap::from([(players[0], 2)])).unwrap();
+        assert_eq!(game.stage, GameStage::Results);
+    }
+
+    #[test]
+    fn test_voting_ends_when_last_non_voter_drops() {
+        let players = vec![PlayerId::new(), PlayerId::new()];
+        let mut game = voting_game(&players);
+        game.submit_votes(players[0], HashMap::from([(players[1], 5)])).unwrap();
+        
+        game.set_connected_players(&players[..1]);
+        assert_eq!(game.stage, GameStage::Results);
+    }
+
+    #[test]
+    fn test_nobody_connected_waits_for_deadline() {
+        let players = vec![PlayerId::new(), PlayerId::new()];
+        let mut game = voting_game(&players);
+        game.set_connected_players(&[]);
+        assert_eq!(game.stage, GameStage::Voting);
+    }
+
+    #[test]
+    fn test_vote_validation() {
+        let players = vec![PlayerId::new(), PlayerId::new()];
+        let mut game = voting_game(&players);
+        let stranger = PlayerId::new();
+        
+        assert!(game.submit_votes(stranger, HashMap::new()).is_err());
+        assert!(game.submit_votes(players[0], HashMap::from([(stranger, 3)])).is_err());
+        assert!(game.submit_votes(players[0], HashMap::from([(players[0], 3)])).is_err());
+        assert!(game.submit_votes(players[0], HashMap::from([(players[1], 6)])).is_err());
+    }
+
+    #[test]
+    fn test_scores_ignore_missing_votes() {
+        let players = vec![PlayerId::new(), PlayerId::new(), PlayerId::new()];
+        let mut game = voting_game(&players);
+        // Carol never votes; Alice rates only Bob
+        game.submit_votes(players[0], HashMap::from([(players[1], 4)])).unwrap();
+        game.submit_votes(players[1], HashMap::from([(players[0], 2), (players[2], 5)])).unwrap();
+        
+        let scores = game.calculate_scores().players;
+        assert_eq!(scores[&players[0]], 2.0);
+        assert_eq!(scores[&players[1]], 4.0);
+        assert_eq!(scores[&players[2]], 5.0);
+        
+        let mut game = voting_game(&players);
+        game.submit_votes(players[0], HashMap::from([(players[1], 4)])).unwrap();
+        assert!(!game.calculate_scores().players.contains_key(&players[2]));
+    }
+
+    #[test]
+    fn test_audience_votes_are_scored_separately() {
+        let players = vec![PlayerId::new(), PlayerId::new()];
+        let mut game = voting_game(&players);
+        let dana = SpectatorId::new();
+        let eve = SpectatorId::new();
+        
+        game.submit_audience_votes(dana, HashMap::from([(players[0], 5), (players[1], 1)])).unwrap();
+        game.submit_audience_votes(eve, HashMap::from([(players[0], 3)])).unwrap();
+        assert!(game.submit_audience_votes(eve, HashMap::from([(PlayerId::new(), 3)])).is_err());
+        assert!(game.submit_audience_votes(eve, HashMap::from([(players[1], 6)])).is_err());
+        
+        // The audience never completes voting on its own
+        assert_eq!(game.stage, GameStage::Voting);
+        game.submit_votes(players[0], HashMap::from([(players[1], 4)])).unwrap();
+        game.submit_votes(players[1], HashMap::from([(players[0], 2)])).unwrap();
+        assert_eq!(game.stage, GameStage::Results);
+        assert!(game.submit_audience_votes(dana, HashMap::new()).is_err());
+        
+        let scores = game.calculate_scores();
+        assert_eq!(scores.players[&players[0]], 2.0);
+        assert_eq!(scores.players[&players[1]], 4.0);
+        assert_eq!(scores.audience[&players[0]], 4.0);
+        assert_eq!(scores.audience[&players[1]], 1.0);
+    }
+
+    #[test]
+    fn test_late_player_takes_turns_from_next_round() {
+        use crate::image_generator::PlaceholderImageGenerator;
+        let players = vec![PlayerId::new(), PlayerId::new()];
+        let settings = GameSettings { rounds: 2, allow_late_join: true, ..GameSettings::default() };
+        let mut game = reveal_game(&players).with_settings(settings);
+        let carol = PlayerId::new();
+        assert!(game.add_late_player(carol, "A kite".to_string()).is_err());
+        
+        game.next_stage();
+        game.add_late_player(carol, "A kite".to_string()).unwrap();
+        assert!(game.add_late_player(carol, "A kite".to_string()).is_err());
+        assert_eq!(game.player_current_objects[&carol], "A kite");
+        
+        // The round in progress is unaffected
+        game.submit_action(players[0], None, &PlaceholderImageGenerator).unwrap();
+        assert_eq!(game.current_player(), Some(players[1]));
+        game.submit_action(players[1], None, &PlaceholderImageGenerator).unwrap();
+        assert_eq!(game.players_in_order, vec![players[0], players[1], carol]);
+        assert_eq!(game.current_round, 1);
+        assert_eq!(game.current_player(), Some(players[0]));
+        
+        game.submit_action(players[0], None, &PlaceholderImageGenerator).unwrap();
+        game.submit_action(players[1], None, &PlaceholderImageGenerator).unwrap();
+        assert_eq!(game.current_player(), Some(carol));
+        game.submit_action(carol, Some(0), &PlaceholderImageGenerator).unwrap();
+        assert_eq!(game.stage, GameStage::Voting);
+        assert!(game.eligible_voters().contains(&carol));
+    }
+
+    #[test]
+    fn test_late_player_joining_in_last_round_votes() {
+        let players = vec![PlayerId::new(), PlayerId::new()];
+        let mut game = reveal_game(&players);
+        let carol = PlayerId::new();
+        
+        game.next_stage();
+        assert!(!game.accepts_late_joins());
+        assert!(game.add_late_player(carol, "A kite".to_string()).is_err());
+        
+        game.settings.allow_late_join = true;
+        game.add_late_player(carol, "A kite".to_string()).unwrap();
+        game.next_stage();
+        assert_eq!(game.stage, GameStage::Voting);
+        assert!(!game.accepts_late_joins());
+        game.submit_votes(carol, HashMap::from([(players[0], 5)])).unwrap();
+    }
 }
@@ -0,0 +1,448 @@
+//! Event-sourced record of room mutations, with deterministic replay.
+//!
+//! The RoomManager appends a [`LoggedEvent`] for every successful mutation of
+//! a room. Each event holds the command's inputs plus whatever chance or the
+//! clock decided along the way (IDs, room code, drawn options, timestamps,
+//! generated images), so [`replay`] rebuilds an identical [`Room`] without the
+//! RNG, the system clock or an image backend.
+//!
+//! Unlike [`RoomEvent`](crate::events::RoomEvent), which tells clients what
+//! changed, these events are complete enough to reproduce a game from a bug
+//! report.
+
+use crate::assets::ModifierOption;
+use crate::errors::{ImageError, ReplayError};
+use crate::game::GameState;
+use crate::image_generator::{ImageGenerator, ImageRequest};
+use crate::player::Player;
+use crate::room::{Participant, Room, RoomState};
+use crate::settings::GameSettings;
+use crate::spectator::Spectator;
+use crate::types::{AvatarId, ImageId, PlayerId, RoomId, SpectatorId};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A domain event with the time it happened.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoggedEvent {
+    /// When the mutation happened (Unix seconds).
+    pub at: u64,
+
+    /// What happened.
+    pub event: GameEvent,
+}
+
+/// A single mutation of a room or its game.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GameEvent {
+    /// The room was created.
+    RoomCreated {
+        room_id: RoomId,
+        code: String,
+        #[serde(default)]
+        settings: GameSettings,
+        /// Seed given at creation, if any.
+        #[serde(default)]
+        seed: Option<u64>,
+    },
+
+    /// The host changed the game settings.
+    SettingsChanged { settings: GameSettings },
+
+    /// A player joined the lobby.
+    PlayerJoined {
+        player_id: PlayerId,
+        nickname: String,
+        avatar_id: AvatarId,
+    },
+
+    /// A player who joined mid-game was given their starting object.
+    LateJoined { player_id: PlayerId, starting_object: String },
+
+    /// A disconnected player rejoined.
+    PlayerReconnected { player_id: PlayerId },
+
+    /// A player left the room.
+    PlayerLeft { player_id: PlayerId },
+
+    /// An audience member started watching.
+    SpectatorJoined { spectator_id: SpectatorId, nickname: String },
+
+    /// An audience member stopped watching.
+    SpectatorLeft { spectator_id: SpectatorId },
+
+    /// The display or a player lost its connection.
+    Disconnected { participant: Participant },
+
+    /// The display or a player regained its connection.
+    Reconnected { participant: Participant },
+
+    /// Host privileges passed to another participant.
+    HostChanged { host: Participant },
+
+    /// The game caught up with who is connected (skipping or resuming turns).
+    PresenceSynced { draw: Draw },
+
+    /// The game started with this initial state (goal, objects and images).
+    GameStarted { game: Box<GameState> },
+
+    /// The game moved to its next stage.
+    StageAdvanced { draw: Draw },
+
+    /// A player acknowledged the goal reveal.
+    RevealAcknowledged { player_id: PlayerId, draw: Draw },
+
+    /// A player took (or skipped) their turn.
+    ActionSubmitted {
+        player_id: PlayerId,
+        option_index: Option<usize>,
+        /// The current image after the action.
+        image: ImageId,
+        draw: Draw,
+    },
+
+    /// A player submitted their votes.
+    VotesSubmitted {
+        voter_id: PlayerId,
+        votes: HashMap<PlayerId, u8>,
+        draw: Draw,
+    },
+
+    /// An audience member submitted their votes.
+    AudienceVotesSubmitted {
+        spectator_id: SpectatorId,
+        votes: HashMap<PlayerId, u8>,
+    },
+}
+
+/// What chance and the clock decided during a game mutation.
+///
+/// Replay applies the command, then restores these fields.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Draw {
+    /// Options offered for the turn in progress afterwards.
+    pub current_options: Vec<ModifierOption>,
+
+    /// Start of the turn in progress afterwards (Unix seconds).
+    pub turn_start_time: Option<u64>,
+
+    /// Start of the stage in progress afterwards (Unix seconds).
+    pub stage_start_time: u64,
+}
+
+impl Draw {
+    /// Capture the drawn fields of a game after a mutation.
+    pub fn of(game: &GameState) -> Self {
+        Self {
+            current_options: game.current_options.clone(),
+            turn_start_time: game.turn_start_time,
+            stage_start_time: game.stage_start_time,
+        }
+    }
+
+    fn apply(&self, game: &mut GameState) {
+        game.current_options = self.current_options.clone();
+        game.turn_start_time = self.turn_start_time;
+        game.stage_start_time = self.stage_start_time;
+    }
+}
+
+/// Rebuild a room from its event log.
+///
+/// The first event must be [`GameEvent::RoomCreated`]. Fails with the index
+/// of the first event the room rejects.
+pub fn replay(events: &[LoggedEvent]) -> Result<Room, ReplayError> {
+    let Some((first, rest)) = events.split_first() else {
+        return Err(ReplayError::MissingRoomCreated);
+    };
+    let GameEvent::RoomCreated { room_id, code, settings, seed } = &first.event else {
+        return Err(ReplayError::MissingRoomCreated);
+    };
+
+    let mut room = Room::new(code.clone());
+    room.id = *room_id;
+    room.settings = settings.clone();
+    room.seed = *seed;
+    room.last_activity = first.at;
+
+    for (offset, logged) in rest.iter().enumerate() {
+        apply(&mut room, logged.at, &logged.event)
+            .map_err(|reason| ReplayError::Rejected { index: offset + 1, reason })?;
+    }
+    Ok(room)
+}
+
+/// Apply one event, recorded at `at`, to a room as the RoomManager did.
+fn apply(room: &mut Room, at: u64, event: &GameEvent) -> Result<(), String> {
+    room.last_activity = at;
+    match event {
+        GameEvent::RoomCreated { .. } => return Err("Room already created".to_string()),
+        GameEvent::PlayerJoined { player_id, nickname, avatar_id } => {
+            room.add_player(Player {
+                id: *player_id,
+                nickname: nickname.clone(),
+                avatar_id: *avatar_id,
+                connected: true,
+                connected_since: at,
+            });
+        }
+        GameEvent::LateJoined { player_id, starting_object } => {
+            let game = game_mut(room)?;
+            // Spend the draw the manager made, then seat the object it drew
+            // (the same one, unless the deck changed between versions)
+            game.draw_late_object();
+            game.add_late_player(*player_id, starting_object.clone())?;
+        }
+        GameEvent::SettingsChanged { settings } => {
+            if room.state != RoomState::Lobby {
+                return Err("Game already started".to_string());
+            }
+            room.settings = settings.clone();
+        }
+        GameEvent::PlayerReconnected { player_id } => {
+            if !room.set_connected(Participant::Player { player_id: *player_id }, true, at) {
+                return Err("Unknown player".to_string());
+            }
+        }
+        GameEvent::PlayerLeft { player_id } => {
+            if !room.remove_player(*player_id) {
+                return Err("Unknown player".to_string());
+            }
+        }
+        GameEvent::SpectatorJoined { spectator_id, nickname } => {
+            room.add_spectator(Spectator { id: *spectator_id, nickname: nickname.clone() });
+        }
+        GameEvent::SpectatorLeft { spectator_id } => {
+            if !room.remove_spectator(*spectator_id) {
+                return Err("Unknown spectator".to_string());
+            }
+        }
+        GameEvent::Disconnected { participant } => {
+            if !room.set_connected(*participant, false, at) {
+                return Err("Unknown player".to_string());
+            }
+        }
+        GameEvent::Reconnected { participant } => {
+            if !room.set_connected(*participant, true, at) {
+                return Err("Unknown player".to_string());
+            }
+        }
+        GameEvent::HostChanged { host } => {
+            room.host = *host;
+        }
+        GameEvent::GameStarted { game } => {
+            if room.state != RoomState::Lobby {
+                return Err("Game already started".to_string());
+            }
+            room.start_game((**game).clone());
+        }
+        GameEvent::StageAdvanced { draw } => {
+            let game = game_mut(room)?;
+            game.next_stage();
+            draw.apply(game);
+        }
+        GameEvent::PresenceSynced { draw } => {
+            let connected = connected_players(room);
+            let game = game_mut(room)?;
+            game.set_connected_players(&connected);
+            draw.apply(game);
+        }
+        GameEvent::RevealAcknowledged { player_id, draw } => {
+            let connected = connected_players(room);
+            let game = game_mut(room)?;
+            game.acknowledge_reveal(*player_id, &connected)?;
+            draw.apply(game);
+        }
+        GameEvent::ActionSubmitted { player_id, option_index, image, draw } => {
+            let game = game_mut(room)?;
+            game.submit_action(*player_id, *option_index, &RecordedImage(image.clone()))?;
+            draw.apply(game);
+        }
+        GameEvent::VotesSubmitted { voter_id, votes, draw } => {
+            let game = game_mut(room)?;
+            game.submit_votes(*voter_id, votes.clone())?;
+            draw.apply(game);
+        }
+        GameEvent::AudienceVotesSubmitted { spectator_id, votes } => {
+            game_mut(room)?.submit_audience_votes(*spectator_id, votes.clone())?;
+        }
+    }
+    Ok(())
+}
+
+fn connected_players(room: &Room) -> Vec<PlayerId> {
+    room.players.iter()
+        .filter(|p| p.connected)
+        .map(|p| p.id)
+        .collect()
+}
+
+fn game_mut(room: &mut Room) -> Result<&mut GameState, String> {
+    room.game.as_mut().ok_or_else(|| "Game not started".to_string())
+}
+
+/// Image "generator" that hands back the image recorded in the log.
+#[derive(Debug)]
+struct RecordedImage(ImageId);
+
+impl ImageGenerator for RecordedImage {
+    fn generate(&self, _request: &ImageRequest) -> Result<ImageId, ImageError> {
+        Ok(self.0.clone())
+    }
+}
+
+/// Current Unix time in seconds.
+pub(crate) fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GameStage;
+    use crate::room_manager::RoomManager;
+    use crate::session::Rejoin;
+
+    /// Play a full game through the RoomManager, returning the room ID.
+    fn play_game(manager: &mut RoomManager) -> RoomId {
+        let (room_id, code) = manager.create_room();
+        let settings = GameSettings {
+            rounds: 3,
+            options_per_turn: 5,
+            allow_late_join: true,
+            ..GameSettings::default()
+        };
+        manager.update_settings(&room_id, settings).unwrap();
+        let mut players = Vec::new();
+        for name in ["Alice", "Bob", "Carol", "Dave"] {
+            let (_, id) = manager.join_room(&code, name.to_string(), AvatarId::new(1)).unwrap();
+            players.push(id);
+        }
+        manager.leave_room(room_id, players.pop().unwrap()).unwrap();
+        manager.disconnect(room_id, Participant::Player { player_id: players[1] }).unwrap();
+        manager.rejoin_room(&code, Rejoin::Nickname("Bob".to_string())).unwrap();
+        manager.start_game(&room_id).unwrap();
+        let (_, dana) = manager.join_as_spectator(&code, "Dana".to_string()).unwrap();
+        let (_, eve) = manager.join_as_spectator(&code, "Eve".to_string()).unwrap();
+        manager.leave_as_spectator(room_id, eve).unwrap();
+        manager.acknowledge_reveal(&room_id, players[0]).unwrap();
+        manager.next_stage(&room_id).unwrap();
+
+        // Three rounds: choose, skip and let one turn expire. Erin joins
+        // during the last round, in time to vote
+        let mut late = None;
+        for round in 0..3 {
+            if round == 2 {
+                late = Some(manager.join_room(&code, "Erin".to_string(), AvatarId::new(2)).unwrap().1);
+            }
+            for (i, player) in players.iter().enumerate() {
+                match (round + i) % 3 {
+                    0 => manager.submit_action(&room_id, *player, Some(i)).unwrap(),
+                    1 => manager.submit_action(&room_id, *player, None).unwrap(),
+                    _ => {
+                        manager.expire_turn(&room_id, u64::MAX).unwrap();
+                    }
+                }
+            }
+        }
+
+        players.extend(late);
+        manager.submit_audience_votes(&room_id, dana, HashMap::from([(players[0], 5)])).unwrap();
+        for voter in &players {
+            let votes = players.iter()
+                .filter(|p| *p != voter)
+                .map(|p| (*p, 4))
+                .collect();
+            manager.submit_votes(&room_id, *voter, votes).unwrap();
+        }
+        room_id
+    }
+
+    #[test]
+    fn test_replay_rebuilds_identical_room() {
+        let mut manager = RoomManager::new();
+        let room_id = play_game(&mut manager);
+        let room = manager.get_room(&room_id).unwrap();
+        assert_eq!(room.game.as_ref().unwrap().stage, GameStage::Results);
+
+        let log = manager.game_log(&room_id).unwrap();
+        let replayed = replay(log).expect("Log should replay");
+
+        assert_eq!(
+            serde_json::to_value(&replayed).unwrap(),
+            serde_json::to_value(room).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_replay_reproduces_skipped_turns() {
+        let mut manager = RoomManager::new();
+        let (room_id, code) = manager.create_room();
+        let mut players = Vec::new();
+        for name in ["Alice", "Bob", "Carol"] {
+            let (_, id) = manager.join_room(&code, name.to_string(), AvatarId::new(1)).unwrap();
+            players.push(id);
+        }
+        manager.start_game(&room_id).unwrap();
+        manager.next_stage(&room_id).unwrap();
+        manager.disconnect(room_id, Participant::Player { player_id: players[0] }).unwrap();
+        manager.submit_action(&room_id, players[1], Some(0)).unwrap();
+        manager.reconnect(room_id, Participant::Player { player_id: players[0] }).unwrap();
+        manager.disconnect(room_id, Participant::Display).unwrap();
+        
+        let room = manager.get_room(&room_id).unwrap();
+        let replayed = replay(manager.game_log(&room_id).unwrap()).expect("Log should replay");
+        assert_eq!(
+            serde_json::to_value(&replayed).unwrap(),
+            serde_json::to_value(room).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_log_round_trips_through_json() {
+        let mut manager = RoomManager::new();
+        let room_id = play_game(&mut manager);
+        let log = manager.game_log(&room_id).unwrap();
+
+        let json = serde_json::to_string(log).expect("Should serialize");
+        let parsed: Vec<LoggedEvent> = serde_json::from_str(&json).expect("Should deserialize");
+        assert_eq!(parsed, log);
+        assert!(replay(&parsed).is_ok());
+    }
+
+    #[test]
+    fn test_replay_requires_room_created() {
+        assert!(matches!(replay(&[]), Err(ReplayError::MissingRoomCreated)));
+
+        let event = LoggedEvent { at: 0, event: GameEvent::PlayerLeft { player_id: PlayerId::new() } };
+        assert!(matches!(replay(&[event]), Err(ReplayError::MissingRoomCreated)));
+    }
+
+    #[test]
+    fn test_replay_reports_rejected_event() {
+        let events = vec![
+            LoggedEvent {
+                at: 0,
+                event: GameEvent::RoomCreated {
+                    room_id: RoomId::new(),
+                    code: "ABC123".to_string(),
+                    settings: GameSettings::default(),
+                    seed: None,
+                },
+            },
+            LoggedEvent {
+                at: 1,
+                event: GameEvent::PlayerLeft { player_id: PlayerId::new() },
+            },
+        ];
+
+        match replay(&events) {
+            Err(ReplayError::Rejected { index, .. }) => assert_eq!(index, 1),
+            other => panic!("Expected rejection, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! Image generation interface for goal, starting and per-turn images.
+//!
+//! The domain only describes *what* should be drawn; the server decides which
+//! backend draws it by handing an [`ImageGenerator`] to the [`RoomManager`].
+//!
+//! [`RoomManager`]: crate::room_manager::RoomManager
+
+use crate::errors::ImageError;
+use crate::object::GameObject;
+use crate::types::ImageId;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A request for a new image.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImageRequest {
+    /// The target image the group is trying to reach.
+    Goal {
+        /// Description of the communal goal.
+        goal: String,
+    },
+
+    /// The shared image at the start of the game, showing every starting object.
+    Starting {
+        /// Description of the communal goal.
+        goal: String,
+        /// Starting objects in turn order.
+        objects: Vec<GameObject>,
+    },
+
+    /// A transformation of the current image after a player's turn.
+    Transformation {
+        /// Description of the communal goal.
+        goal: String,
+        /// The image being transformed.
+        previous: ImageId,
+        /// Current objects in turn order, including the modified one.
+        objects: Vec<GameObject>,
+        /// The modifier chosen this turn.
+        modifier: String,
+    },
+}
+
+impl ImageRequest {
+    /// Short name of the request kind, used as an image ID prefix.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ImageRequest::Goal { .. } => "goal",
+            ImageRequest::Starting { .. } => "start",
+            ImageRequest::Transformation { .. } => "turn",
+        }
+    }
+
+    /// Stable 64-bit fingerprint of the request contents.
+    ///
+    /// Uses FNV-1a so the value is identical across builds and platforms.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hash = Fnv1a::new();
+        hash.write(self.kind());
+        match self {
+            ImageRequest::Goal { goal } => hash.write(goal),
+            ImageRequest::Starting { goal, objects } => {
+                hash.write(goal);
+                objects.iter().for_each(|o| hash.write_object(o));
+            }
+            ImageRequest::Transformation { goal, previous, objects, modifier } => {
+                hash.write(goal);
+                hash.write(previous.as_str());
+                objects.iter().for_each(|o| hash.write_object(o));
+                hash.write(modifier);
+            }
+        }
+        hash.finish()
+    }
+}
+
+/// A backend capable of producing images for the game.
+pub trait ImageGenerator: fmt::Debug + Send + Sync {
+    /// Produce an image for the request and return its ID.
+    fn generate(&self, request: &ImageRequest) -> Result<ImageId, ImageError>;
+
+    /// Delete an image no room shows any more.
+    ///
+    /// Backends that keep nothing per image need not implement this.
+    fn discard(&self, _image: &ImageId) -> Result<(), ImageError> {
+        Ok(())
+    }
+}
+
+/// Generator that returns stable placeholder IDs without drawing anything.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PlaceholderImageGenerator;
+
+impl ImageGenerator for PlaceholderImageGenerator {
+    fn generate(&self, request: &ImageRequest) -> Result<ImageId, ImageError> {
+        Ok(ImageId::new(format!("{}_{:016x}", request.kind(), request.fingerprint())))
+    }
+}
+
+/// Minimal FNV-1a hasher over length-prefixed strings.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, s: &str) {
+        for byte in (s.len() as u64).to_le_bytes().iter().chain(s.as_bytes()) {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    /// Objects are hashed by their parts, since two objects can read the
+    /// same ("A giant floating taco") yet be drawn differently.
+    fn write_object(&mut self, object: &GameObject) {
+        self.write(&serde_json::to_string(object).unwrap_or_default());
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transformation(modifier: &str) -> ImageRequest {
+        ImageRequest::Transformation {
+            goal: "A wizard cat".to_string(),
+            previous: ImageId::new("start_0"),
+            objects: vec![GameObject::new("A wizard cat")],
+            modifier: modifier.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_placeholder_is_deterministic() {
+        let generator = PlaceholderImageGenerator;
+        let a = generator.generate(&transformation("wearing a cape")).unwrap();
+        let b = generator.generate(&transformation("wearing a cape")).unwrap();
+        assert_eq!(a, b);
+        assert!(a.as_str().starts_with("turn_"));
+    }
+
+    #[test]
+    fn test_different_requests_get_different_ids() {
+        let generator = PlaceholderImageGenerator;
+        let a = generator.generate(&transformation("wearing a cape")).unwrap();
+        let b = generator.generate(&transformation("wearing a tutu")).unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_request_kinds() {
+        let goal = ImageRequest::Goal { goal: "x".to_string() };
+        let start = ImageRequest::Starting { goal: "x".to_string(), objects: vec![] };
+        assert_eq!(goal.kind(), "goal");
+        assert_eq!(start.kind(), "start");
+        assert_eq!(transformation("y").kind(), "turn");
+    }
+
+    #[test]
+    fn test_fingerprint_separates_fields() {
+        // Length prefixes keep "ab" + "c" distinct from "a" + "bc".
+        let a = ImageRequest::Starting { goal: "ab".to_string(), objects: vec![GameObject::new("c")] };
+        let b = ImageRequest::Starting { goal: "a".to_string(), objects: vec![GameObject::new("bc")] };
+        assert_ne!(a.fingerprint(), b.fingerprint());
+    }
+
+    #[test]
+    fn test_fingerprint_tells_same_reading_objects_apart() {
+        let mut giant = GameObject::new("A floating taco");
+        giant.apply(&crate::object::Modification::parse("that is giant"));
+        let subject = GameObject::new("A giant floating taco");
+        assert_eq!(giant.to_string(), subject.to_string());
+
+        let request = |object: GameObject| ImageRequest::Starting { goal: "x".to_string(), objects: vec![object] };
+        assert_ne!(request(giant).fingerprint(), request(subject).fingerprint());
+    }
+}
@@ -19,14 +19,37 @@ pub mod game;
 pub mod errors;
 pub mod room_manager;
 pub mod assets;
+pub mod content;
+pub mod evaluation;
+pub mod events;
+pub mod game_log;
+pub mod image_generator;
+pub mod object;
+pub mod procedural_images;
+pub mod rng;
+pub mod session;
+pub mod settings;
+pub mod spectator;
 
 // Re-export commonly used types at crate root
-pub use game::{GameOutcome, GameState, PlayerAction};
+pub use game::{GameOutcome, GameState, PlayerAction, Scores};
 pub use player::Player;
-pub use room::{Room, RoomState};
-pub use types::{AvatarId, ImageId, OptionId, PlayerId, RoomId};
-pub use errors::{RoomError, JoinError};
-pub use room_manager::RoomManager;
+pub use room::{IdleTimeouts, Participant, Room, RoomState};
+pub use types::{AvatarId, ImageId, OptionId, PlayerId, RoomId, SpectatorId};
+pub use assets::{ModifierCategory, ModifierOption};
+pub use content::ContentPack;
+pub use errors::{RoomError, JoinError, ImageError, ReplayError, ContentError};
+pub use evaluation::GameEvaluation;
+pub use events::RoomEvent;
+pub use game_log::{replay, GameEvent, LoggedEvent};
+pub use object::{GameObject, Modification};
+pub use image_generator::{ImageGenerator, ImageRequest, PlaceholderImageGenerator};
+pub use procedural_images::ProceduralImageGenerator;
+pub use rng::GameRng;
+pub use room_manager::{RoomManager, RoomsSnapshot};
+pub use session::{Rejoin, Session, SessionToken};
+pub use settings::{DisconnectPolicy, GameSettings};
+pub use spectator::Spectator;
 
 pub fn version() -> &'static str {
     env!("CARGO_PKG_VERSION")
@@ -0,0 +1,435 @@
+//! Structured game objects and the modifications players make to them.
+//!
+//! A player's object is kept as parts (subject, size, material, color,
+//! attributes, accessories, location) rather than one growing string. Each modifier is
+//! parsed into a typed [`Modification`] that adds a part, replaces a part it
+//! contradicts (a tiny object made giant, a second hat), or changes nothing
+//! when the object already has it. [`GameObject`]'s `Display` renders the
+//! parts as one natural-language description.
+
+use crate::assets::{ModifierCategory, ModifierOption};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// How big an object is, when not its usual size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Size {
+    Tiny,
+    Small,
+    Large,
+    Giant,
+}
+
+impl Size {
+    fn parse(word: &str) -> Option<Self> {
+        match word {
+            "tiny" => Some(Self::Tiny),
+            "small" => Some(Self::Small),
+            "large" | "big" => Some(Self::Large),
+            "giant" | "huge" => Some(Self::Giant),
+            _ => None,
+        }
+    }
+
+    /// The size word as it reads in a description ("giant").
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Tiny => "tiny",
+            Self::Small => "small",
+            Self::Large => "large",
+            Self::Giant => "giant",
+        }
+    }
+}
+
+/// How an accessory is attached to an object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessoryKind {
+    /// "wearing a top hat"; one item per body slot (head, eyes, back, feet).
+    Worn,
+    /// "holding a balloon"; one item at a time.
+    Held,
+    /// "riding a skateboard", "piloting a tiny spaceship"; one at a time.
+    Ridden,
+    /// "with bat wings"; any number.
+    Feature,
+}
+
+/// Something an object wears, holds, rides or has.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Accessory {
+    pub kind: AccessoryKind,
+
+    /// The item, without its verb ("a top hat").
+    pub item: String,
+}
+
+impl Accessory {
+    /// Whether wearing, holding or riding `other` rules this accessory out.
+    fn conflicts_with(&self, other: &Accessory) -> bool {
+        if self.kind != other.kind {
+            return false;
+        }
+        match self.kind {
+            AccessoryKind::Worn => worn_slot(&self.item).is_some() && worn_slot(&self.item) == worn_slot(&other.item),
+            AccessoryKind::Held | AccessoryKind::Ridden => true,
+            AccessoryKind::Feature => false,
+        }
+    }
+}
+
+/// Body slot a worn item occupies, if it is known to occupy one.
+fn worn_slot(item: &str) -> Option<&'static str> {
+    const SLOTS: &[(&str, &[&str])] = &[
+        ("head", &["hat", "helmet", "crown", "cap", "head"]),
+        ("eyes", &["glasses", "monocle", "goggles"]),
+        ("back", &["cape", "jetpack", "backpack"]),
+        ("feet", &["shoes", "boots", "slippers"]),
+    ];
+    SLOTS.iter()
+        .find(|(_, words)| item.split_whitespace().any(|word| words.contains(&word)))
+        .map(|(slot, _)| *slot)
+}
+
+/// A typed change to an object, parsed from a modifier.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Modification {
+    /// "that is giant": replaces any other size.
+    Size(Size),
+    /// "made of jelly": replaces any other material.
+    Material(String),
+    /// "in outer space", "flying through the clouds": replaces any other
+    /// location.
+    Location(String),
+    /// "painted bright pink": replaces any other color. Only offered
+    /// options in the color category are read as colors.
+    Color(String),
+    /// "wearing a cape", "holding a balloon", ...
+    Accessory(Accessory),
+    /// Anything else ("covered in glitter"); replaces an attribute starting
+    /// with the same word ("covered in cobwebs").
+    Attribute(String),
+}
+
+impl Modification {
+    /// Read a modifier such as "wearing a top hat" or "that is tiny".
+    pub fn parse(modifier: &str) -> Self {
+        let text = modifier.trim();
+        let text = text.strip_prefix("that is ").unwrap_or(text);
+        if let Some(size) = Size::parse(text) {
+            return Self::Size(size);
+        }
+        if let Some(material) = text.strip_prefix("made of ") {
+            return Self::Material(material.to_string());
+        }
+        if ["in ", "inside ", "at ", "under "].iter().any(|prefix| text.starts_with(prefix)) {
+            return Self::Location(text.to_string());
+        }
+        let accessories = [
+            ("wearing ", AccessoryKind::Worn),
+            ("holding ", AccessoryKind::Held),
+            ("riding ", AccessoryKind::Ridden),
+            ("piloting ", AccessoryKind::Ridden),
+            ("with ", AccessoryKind::Feature),
+        ];
+        for (verb, kind) in accessories {
+            if let Some(item) = text.strip_prefix(verb) {
+                return Self::Accessory(Accessory { kind, item: item.to_string() });
+            }
+        }
+        Self::Attribute(text.to_string())
+    }
+
+    /// Read an offered option, using its category where the wording alone
+    /// is ambiguous ("that is glowing green" is a color, "flying through the
+    /// clouds" is where the object is).
+    pub fn of(option: &ModifierOption) -> Self {
+        match (option.category, Self::parse(&option.text)) {
+            (ModifierCategory::Color, _) => {
+                let text = option.text.trim();
+                Self::Color(text.strip_prefix("that is ").unwrap_or(text).to_string())
+            }
+            (ModifierCategory::Move, Self::Attribute(text)) => Self::Location(text),
+            (_, modification) => modification,
+        }
+    }
+
+    /// The words this modification leaves in a rendered description
+    /// ("a top hat" for "wearing a top hat", "giant" for "that is giant").
+    pub fn phrase(&self) -> String {
+        match self {
+            Self::Size(size) => size.as_str().to_string(),
+            Self::Material(material) => format!("made of {}", material),
+            Self::Location(text) | Self::Color(text) | Self::Attribute(text) => text.clone(),
+            Self::Accessory(accessory) => accessory.item.clone(),
+        }
+    }
+}
+
+/// What applying a [`Modification`] did to an object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Effect {
+    /// A new part was added.
+    Added,
+    /// A contradicting part was replaced; holds the part that went.
+    Replaced(String),
+    /// The object already had it.
+    Unchanged,
+}
+
+/// A player's object, kept in parts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameObject {
+    /// What the object is ("A surfing giraffe").
+    pub subject: String,
+
+    /// Color, when changed ("painted bright pink").
+    #[serde(default)]
+    pub color: Option<String>,
+
+    /// Descriptions such as "covered in glitter", in the order added.
+    #[serde(default)]
+    pub attributes: Vec<String>,
+
+    /// Things it wears, holds, rides or has, in the order added.
+    #[serde(default)]
+    pub accessories: Vec<Accessory>,
+
+    /// Size, when not its usual size.
+    #[serde(default)]
+    pub size: Option<Size>,
+
+    /// What it is made of, when changed.
+    #[serde(default)]
+    pub material: Option<String>,
+
+    /// Where it is ("in outer space"), when moved.
+    #[serde(default)]
+    pub location: Option<String>,
+}
+
+impl GameObject {
+    /// An unmodified object.
+    pub fn new(subject: impl Into<String>) -> Self {
+        Self {
+            subject: subject.into(),
+            color: None,
+            attributes: Vec::new(),
+            accessories: Vec::new(),
+            size: None,
+            material: None,
+            location: None,
+        }
+    }
+
+    /// Apply a modification, replacing whatever it contradicts.
+    pub fn apply(&mut self, modification: &Modification) -> Effect {
+        match modification {
+            Modification::Size(size) => replace(&mut self.size, *size, |size| size.as_str().to_string()),
+            Modification::Material(material) => replace(&mut self.material, material.clone(), |m| format!("made of {}", m)),
+            Modification::Location(location) => replace(&mut self.location, location.clone(), Clone::clone),
+            Modification::Color(color) => replace(&mut self.color, color.clone(), Clone::clone),
+            Modification::Accessory(accessory) => {
+                if self.accessories.contains(accessory) {
+                    return Effect::Unchanged;
+                }
+                match self.accessories.iter().position(|a| a.conflicts_with(accessory)) {
+                    Some(i) => Effect::Replaced(std::mem::replace(&mut self.accessories[i], accessory.clone()).item),
+                    None => {
+                        self.accessories.push(accessory.clone());
+                        Effect::Added
+                    }
+                }
+            }
+            Modification::Attribute(attribute) => {
+                if self.attributes.contains(attribute) {
+                    return Effect::Unchanged;
+                }
+                let first_word = |text: &str| text.split_whitespace().next().map(str::to_string);
+                match self.attributes.iter().position(|a| first_word(a) == first_word(attribute)) {
+                    Some(i) => Effect::Replaced(std::mem::replace(&mut self.attributes[i], attribute.clone())),
+                    None => {
+                        self.attributes.push(attribute.clone());
+                        Effect::Added
+                    }
+                }
+            }
+        }
+    }
+
+    /// Items of accessories of one kind.
+    fn items(&self, kind: AccessoryKind) -> Vec<&str> {
+        self.accessories.iter()
+            .filter(|a| a.kind == kind)
+            .map(|a| a.item.as_str())
+            .collect()
+    }
+}
+
+/// Set `slot` to `value`, reporting what it held before.
+fn replace<T: PartialEq>(slot: &mut Option<T>, value: T, describe: impl Fn(&T) -> String) -> Effect {
+    match slot.replace(value) {
+        None => Effect::Added,
+        Some(previous) if Some(&previous) == slot.as_ref() => Effect::Unchanged,
+        Some(previous) => Effect::Replaced(describe(&previous)),
+    }
+}
+
+/// "a", "a and b", "a, b and c".
+fn join_list(items: &[&str]) -> String {
+    match items.split_last() {
+        None => String::new(),
+        Some((last, [])) => last.to_string(),
+        Some((last, rest)) => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+impl fmt::Display for GameObject {
+    /// "A giant surfing giraffe made of jelly, that is covered in glitter,
+    /// wearing a top hat and sunglasses, holding a balloon in outer space".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.size {
+            Some(size) => {
+                let (article, rest) = match self.subject.split_once(' ') {
+                    Some((article @ ("A" | "An" | "a" | "an"), rest)) => (Some(article), rest),
+                    _ => (None, self.subject.as_str()),
+                };
+                match article {
+                    // No size word starts with a vowel: "An octopus" -> "A giant octopus"
+                    Some(article) => {
+                        let article = if article.starts_with('A') { "A" } else { "a" };
+                        write!(f, "{} {} {}", article, size.as_str(), rest)?;
+                    }
+                    None => write!(f, "{} {}", size.as_str(), rest)?,
+                }
+            }
+            None => write!(f, "{}", self.subject)?,
+        }
+        if let Some(material) = &self.material {
+            write!(f, " made of {}", material)?;
+        }
+
+        let attributes: Vec<&str> = self.color.iter().chain(&self.attributes).map(String::as_str).collect();
+        let clauses = [
+            ("that is", attributes),
+            ("wearing", self.items(AccessoryKind::Worn)),
+            ("with", self.items(AccessoryKind::Feature)),
+            ("holding", self.items(AccessoryKind::Held)),
+            ("riding", self.items(AccessoryKind::Ridden)),
+        ];
+        for (verb, items) in clauses {
+            if !items.is_empty() {
+                write!(f, ", {} {}", verb, join_list(&items))?;
+            }
+        }
+
+        if let Some(location) = &self.location {
+            write!(f, " {}", location)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modified(subject: &str, modifiers: &[&str]) -> GameObject {
+        let mut object = GameObject::new(subject);
+        for modifier in modifiers {
+            object.apply(&Modification::parse(modifier));
+        }
+        object
+    }
+
+    #[test]
+    fn test_parse_modifiers() {
+        assert_eq!(Modification::parse("that is giant"), Modification::Size(Size::Giant));
+        assert_eq!(Modification::parse("made of jelly"), Modification::Material("jelly".to_string()));
+        assert_eq!(
+            Modification::parse("wearing a top hat"),
+            Modification::Accessory(Accessory { kind: AccessoryKind::Worn, item: "a top hat".to_string() })
+        );
+        assert_eq!(
+            Modification::parse("piloting a tiny spaceship"),
+            Modification::Accessory(Accessory { kind: AccessoryKind::Ridden, item: "a tiny spaceship".to_string() })
+        );
+        assert_eq!(Modification::parse("that is glowing green"), Modification::Attribute("glowing green".to_string()));
+        assert_eq!(Modification::parse("on fire (safely)"), Modification::Attribute("on fire (safely)".to_string()));
+        assert_eq!(Modification::parse("in outer space"), Modification::Location("in outer space".to_string()));
+    }
+
+    #[test]
+    fn test_phrase_appears_in_rendering() {
+        for modifier in ["that is giant", "made of jelly", "wearing sunglasses", "that is glowing green", "in outer space"] {
+            let object = modified("A wizard cat", &["wearing a top hat", modifier]);
+            let phrase = Modification::parse(modifier).phrase();
+            assert!(object.to_string().contains(&phrase), "{} / {}", object, phrase);
+        }
+    }
+
+    #[test]
+    fn test_option_categories_guide_parsing() {
+        let pink = ModifierOption { category: ModifierCategory::Color, text: "painted bright pink".to_string() };
+        let green = ModifierOption { category: ModifierCategory::Color, text: "that is glowing green".to_string() };
+        assert_eq!(Modification::of(&green), Modification::Color("glowing green".to_string()));
+
+        let mut object = modified("A wizard cat", &["covered in glitter"]);
+        assert_eq!(object.apply(&Modification::of(&pink)), Effect::Added);
+        assert_eq!(object.apply(&Modification::of(&green)), Effect::Replaced("painted bright pink".to_string()));
+        assert_eq!(object.to_string(), "A wizard cat, that is glowing green and covered in glitter");
+
+        let flying = ModifierOption { category: ModifierCategory::Move, text: "flying through the clouds".to_string() };
+        object.apply(&Modification::parse("under the sea"));
+        assert_eq!(object.apply(&Modification::of(&flying)), Effect::Replaced("under the sea".to_string()));
+        assert!(object.to_string().ends_with("covered in glitter flying through the clouds"), "{}", object);
+    }
+
+    #[test]
+    fn test_contradicting_traits_replace_each_other() {
+        let mut object = GameObject::new("A wizard cat");
+        assert_eq!(object.apply(&Modification::parse("that is tiny")), Effect::Added);
+        assert_eq!(object.apply(&Modification::parse("that is giant")), Effect::Replaced("tiny".to_string()));
+        assert_eq!(object.apply(&Modification::parse("that is giant")), Effect::Unchanged);
+
+        object.apply(&Modification::parse("wearing a top hat"));
+        assert_eq!(object.apply(&Modification::parse("wearing a space helmet")), Effect::Replaced("a top hat".to_string()));
+        assert_eq!(object.apply(&Modification::parse("wearing sunglasses")), Effect::Added);
+
+        object.apply(&Modification::parse("covered in glitter"));
+        assert_eq!(object.apply(&Modification::parse("covered in cobwebs")), Effect::Replaced("covered in glitter".to_string()));
+
+        object.apply(&Modification::parse("with bat wings"));
+        assert_eq!(object.apply(&Modification::parse("with fangs")), Effect::Added);
+        assert_eq!(object.size, Some(Size::Giant));
+        assert_eq!(object.accessories.len(), 4);
+    }
+
+    #[test]
+    fn test_render_description() {
+        let object = modified("A wizard cat", &["wearing a cape", "made of jelly", "that is tiny"]);
+        assert_eq!(object.to_string(), "A tiny wizard cat made of jelly, wearing a cape");
+
+        let object = modified("An octopus", &[
+            "that is giant", "covered in glitter", "on fire (safely)", "wearing a top hat",
+            "wearing sunglasses", "holding a balloon", "holding a rubber chicken", "riding a skateboard",
+        ]);
+        assert_eq!(
+            object.to_string(),
+            "A giant octopus, that is covered in glitter and on fire (safely), wearing a top hat and sunglasses, \
+             holding a rubber chicken, riding a skateboard"
+        );
+
+        let object = modified("A sentient toaster", &["in outer space", "with laser eyes", "that is large"]);
+        assert_eq!(object.to_string(), "A large sentient toaster, with laser eyes in outer space");
+    }
+
+    #[test]
+    fn test_unmodified_object_renders_its_subject() {
+        assert_eq!(GameObject::new("A sentient toaster").to_string(), "A sentient toaster");
+        assert_eq!(modified("Steve", &["that is giant"]).to_string(), "giant Steve");
+    }
+}
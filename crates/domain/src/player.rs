@@ -20,6 +20,11 @@ pub struct Player {
     
     /// Whether the player is currently connected.
     pub connected: bool,
+    
+    /// When the player last (re)connected (Unix seconds); decides who
+    /// inherits host privileges.
+    #[serde(default)]
+    pub connected_since: u64,
 }
 
 impl Player {
@@ -30,6 +35,7 @@ impl Player {
             nickname,
             avatar_id,
             connected: true,
+            connected_since: 0,
         }
     }
 
@@ -0,0 +1,421 @@
+//! Offline, CPU-only image backend that draws game text as SVG scenes.
+//!
+//! Every request is rendered deterministically: the goal's location (from
+//! `LOCATIONS` in [`crate::assets`]) picks the backdrop, each object's subject
+//! (from `ANIMALS` or `OBJECTS`) picks its layered shape, and each part the
+//! object was given adds a badge (plus a visual effect for size, glow and
+//! transparency).
+//! Identical requests always produce byte-identical files, so it is safe to use
+//! in CI and for reproducing bug reports.
+//!
+//! Shapes and backdrops only know the built-in lists, not the room's content
+//! packs: a pack's own animals and objects are drawn as a generic box, and a
+//! goal set somewhere the built-in lists don't name gets a backdrop picked
+//! from its length. Badges and effects come from the objects themselves, so
+//! pack modifiers are drawn like built-in ones.
+
+use crate::assets::{ANIMALS, LOCATIONS, OBJECTS};
+use crate::errors::ImageError;
+use crate::image_generator::{ImageGenerator, ImageRequest};
+use crate::object::{GameObject, Size};
+use crate::types::ImageId;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+const WIDTH: u32 = 800;
+const HEIGHT: u32 = 600;
+
+const PALETTE: &[&str] = &[
+    "#e94560", "#4ecca3", "#f9a826", "#3282b8", "#a45de2", "#ff7b54", "#52de97", "#f6416c",
+    "#00b8a9", "#ffde7d", "#6a2c70", "#08d9d6", "#ff2e63", "#95e1d3", "#f38181",
+];
+
+const BACKDROPS: &[(&str, &str)] = &[
+    ("#0b0c2a", "#2d1b69"),
+    ("#ffe29a", "#4fc3f7"),
+    ("#ffb6c1", "#ffd1dc"),
+    ("#e3f2fd", "#90a4ae"),
+    ("#01579b", "#004d40"),
+    ("#1a1a2e", "#e94560"),
+    ("#1b5e20", "#004d40"),
+    ("#81d4fa", "#c5e1a5"),
+    ("#311b92", "#00e5ff"),
+    ("#e1f5fe", "#b3e5fc"),
+    ("#a1887f", "#dce775"),
+    ("#f8bbd0", "#e1bee7"),
+    ("#5d4037", "#ffcc80"),
+    ("#4a148c", "#76ff03"),
+    ("#3e2723", "#bcaaa4"),
+];
+
+/// Generator that writes deterministic SVG files to a local directory.
+///
+/// The returned [`ImageId`] is the file name inside `output_dir`.
+#[derive(Debug, Clone)]
+pub struct ProceduralImageGenerator {
+    output_dir: PathBuf,
+}
+
+impl ProceduralImageGenerator {
+    /// Create a generator that writes into `output_dir` (created on demand).
+    pub fn new(output_dir: impl Into<PathBuf>) -> Self {
+        Self { output_dir: output_dir.into() }
+    }
+
+    /// Directory the images are written to.
+    pub fn output_dir(&self) -> &Path {
+        &self.output_dir
+    }
+}
+
+impl ImageGenerator for ProceduralImageGenerator {
+    fn generate(&self, request: &ImageRequest) -> Result<ImageId, ImageError> {
+        let file_name = format!("{}_{:016x}.svg", request.kind(), request.fingerprint());
+        let path = self.output_dir.join(&file_name);
+
+        // Content-addressed: an existing file already holds this exact image
+        if !path.exists() {
+            std::fs::create_dir_all(&self.output_dir)
+                .map_err(|e| ImageError::Unavailable(format!("{}: {}", self.output_dir.display(), e)))?;
+            std::fs::write(&path, render_svg(request))
+                .map_err(|e| ImageError::Failed(format!("{}: {}", path.display(), e)))?;
+        }
+
+        Ok(ImageId::new(file_name))
+    }
+
+    fn discard(&self, image: &ImageId) -> Result<(), ImageError> {
+        // IDs are bare file names; anything else was not written here
+        if Path::new(image.as_str()).file_name() != Some(image.as_str().as_ref()) {
+            return Ok(());
+        }
+        let path = self.output_dir.join(image.as_str());
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(ImageError::Failed(format!("{}: {}", path.display(), e)))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Render a request as a standalone SVG document.
+pub fn render_svg(request: &ImageRequest) -> String {
+    let mut svg = String::new();
+    let (goal, objects, caption) = match request {
+        ImageRequest::Goal { goal } => (goal, vec![GameObject::new(goal)], format!("GOAL: {}", goal)),
+        ImageRequest::Starting { goal, objects } => (goal, objects.clone(), "The starting line-up".to_string()),
+        ImageRequest::Transformation { goal, objects, modifier, .. } => {
+            (goal, objects.clone(), format!("+ {}", modifier))
+        }
+    };
+
+    let (top, bottom) = backdrop(goal);
+    let _ = write!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = WIDTH,
+        h = HEIGHT
+    );
+    let _ = write!(
+        svg,
+        r#"<defs><linearGradient id="bg" x1="0" y1="0" x2="0" y2="1"><stop offset="0" stop-color="{}"/><stop offset="1" stop-color="{}"/></linearGradient>"#,
+        top, bottom
+    );
+    svg.push_str(r#"<filter id="glow"><feGaussianBlur stdDeviation="6" result="b"/><feMerge><feMergeNode in="b"/><feMergeNode in="SourceGraphic"/></feMerge></filter></defs>"#);
+    let _ = write!(svg, r#"<rect width="{}" height="{}" fill="url(#bg)"/>"#, WIDTH, HEIGHT);
+
+    let count = objects.len().max(1);
+    let columns = (count as f32).sqrt().ceil() as usize;
+    let rows = count.div_ceil(columns);
+    let cell_w = WIDTH as f32 / columns as f32;
+    let cell_h = (HEIGHT - 80) as f32 / rows as f32;
+
+    for (i, object) in objects.iter().enumerate() {
+        let cx = cell_w * ((i % columns) as f32 + 0.5);
+        let cy = 80.0 + cell_h * ((i / columns) as f32 + 0.5);
+        let radius = cell_w.min(cell_h) * 0.28;
+        draw_object(&mut svg, object, cx, cy, radius);
+    }
+
+    let _ = write!(
+        svg,
+        r##"<rect x="0" y="0" width="{}" height="60" fill="#000" fill-opacity="0.45"/><text x="{}" y="38" font-family="sans-serif" font-size="22" fill="#fff" text-anchor="middle">{}</text>"##,
+        WIDTH,
+        WIDTH / 2,
+        escape(&truncate(&caption, 70))
+    );
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Draw one object: a base shape, modifier effects and badges, and a label.
+fn draw_object(svg: &mut String, object: &GameObject, cx: f32, cy: f32, radius: f32) {
+    let text = object.to_string();
+    // One badge per part the object was given, in the order it reads
+    let modifiers: Vec<(usize, String)> = object.size.iter().map(|size| size.as_str().to_string())
+        .chain(object.material.iter().map(|material| format!("made of {}", material)))
+        .chain(object.color.iter().chain(&object.attributes).cloned())
+        .chain(object.accessories.iter().map(|accessory| accessory.item.clone()))
+        .chain(object.location.iter().cloned())
+        .map(|part| (badge_index(&part), part))
+        .collect();
+
+    let mut scale = 1.0;
+    let mut opacity = 1.0;
+    let mut glow = false;
+    match object.size {
+        Some(Size::Giant) => scale = 1.35,
+        Some(Size::Large) => scale = 1.15,
+        Some(Size::Small) => scale = 0.8,
+        Some(Size::Tiny) => scale = 0.6,
+        None => {}
+    }
+    if object.material.as_deref() == Some("jelly") {
+        opacity *= 0.65;
+    }
+    for attribute in object.color.iter().chain(&object.attributes) {
+        match attribute.as_str() {
+            "invisible (mostly)" => opacity *= 0.3,
+            "glowing green" | "on fire (safely)" => glow = true,
+            _ => {}
+        }
+    }
+    let r = radius * scale;
+
+    // The subject alone, so a giant or jelly cat is still a cat
+    let subject = object.subject.to_lowercase();
+    let animal = find_entry(&subject, ANIMALS);
+    let thing = find_entry(&subject, OBJECTS);
+    let fill = PALETTE[(animal.or(thing).unwrap_or(0) + subject.len()) % PALETTE.len()];
+    let filter = if glow { r#" filter="url(#glow)""# } else { "" };
+    let _ = write!(svg, r#"<g opacity="{:.2}"{}>"#, opacity, filter);
+
+    if let Some(index) = animal {
+        // Animals: round body, head and ears
+        let accent = PALETTE[(index + 4) % PALETTE.len()];
+        let _ = write!(
+            svg,
+            r##"<ellipse cx="{cx:.1}" cy="{by:.1}" rx="{rx:.1}" ry="{ry:.1}" fill="{fill}" stroke="#000" stroke-width="2"/><circle cx="{cx:.1}" cy="{hy:.1}" r="{hr:.1}" fill="{accent}" stroke="#000" stroke-width="2"/><circle cx="{lx:.1}" cy="{ey:.1}" r="{er:.1}" fill="{accent}"/><circle cx="{rx2:.1}" cy="{ey:.1}" r="{er:.1}" fill="{accent}"/>"##,
+            cx = cx,
+            by = cy + r * 0.25,
+            rx = r * 0.8,
+            ry = r * 0.6,
+            fill = fill,
+            hy = cy - r * 0.45,
+            hr = r * 0.4,
+            accent = accent,
+            lx = cx - r * 0.3,
+            rx2 = cx + r * 0.3,
+            ey = cy - r * 0.8,
+            er = r * 0.15,
+        );
+    } else {
+        // Objects (and anything unrecognised): rounded box with a highlight
+        let corner = if thing.is_some() { r * 0.25 } else { r * 0.5 };
+        let _ = write!(
+            svg,
+            r##"<rect x="{x:.1}" y="{y:.1}" width="{s:.1}" height="{s:.1}" rx="{c:.1}" fill="{fill}" stroke="#000" stroke-width="2"/><circle cx="{hx:.1}" cy="{hy:.1}" r="{hr:.1}" fill="#fff" fill-opacity="0.5"/>"##,
+            x = cx - r * 0.8,
+            y = cy - r * 0.8,
+            s = r * 1.6,
+            c = corner,
+            fill = fill,
+            hx = cx - r * 0.35,
+            hy = cy - r * 0.35,
+            hr = r * 0.18,
+        );
+    }
+
+    // One badge per modifier, arranged around the object
+    for (n, (index, _)) in modifiers.iter().enumerate() {
+        let angle = -std::f32::consts::FRAC_PI_2 + n as f32 * 0.9;
+        let bx = cx + angle.cos() * r * 1.05;
+        let by = cy + angle.sin() * r * 1.05;
+        let colour = PALETTE[index % PALETTE.len()];
+        let points = star_points(bx, by, r * 0.18, 4 + index % 4);
+        let _ = write!(svg, r##"<polygon points="{}" fill="{}" stroke="#000" stroke-width="1"/>"##, points, colour);
+    }
+    svg.push_str("</g>");
+
+    let _ = write!(
+        svg,
+        r##"<text x="{:.1}" y="{:.1}" font-family="sans-serif" font-size="13" fill="#fff" stroke="#000" stroke-width="0.4" text-anchor="middle">{}</text>"##,
+        cx,
+        cy + radius * 1.45,
+        escape(&truncate(&text, 48))
+    );
+}
+
+/// Backdrop gradient chosen from the location mentioned in `goal`.
+fn backdrop(goal: &str) -> (&'static str, &'static str) {
+    let lower = goal.to_lowercase();
+    let index = find_entry(&lower, LOCATIONS).unwrap_or(lower.len() % BACKDROPS.len());
+    BACKDROPS[index % BACKDROPS.len()]
+}
+
+/// Index of the longest entry of `list` contained in `lower` (case-insensitive).
+fn find_entry(lower: &str, list: &[&str]) -> Option<usize> {
+    list.iter()
+        .enumerate()
+        .filter(|(_, entry)| lower.contains(&entry.to_lowercase()))
+        .max_by_key(|(_, entry)| entry.len())
+        .map(|(i, _)| i)
+}
+
+/// Stable index for a badge's color and shape, so the same part always
+/// gets the same badge.
+fn badge_index(part: &str) -> usize {
+    part.bytes().fold(0usize, |hash, byte| hash.wrapping_mul(31).wrapping_add(byte as usize))
+}
+
+/// Points of a star polygon with `spikes` outer vertices.
+fn star_points(cx: f32, cy: f32, radius: f32, spikes: usize) -> String {
+    (0..spikes * 2)
+        .map(|i| {
+            let r = if i % 2 == 0 { radius } else { radius * 0.45 };
+            let angle = std::f32::consts::PI * i as f32 / spikes as f32 - std::f32::consts::FRAC_PI_2;
+            format!("{:.1},{:.1}", cx + angle.cos() * r, cy + angle.sin() * r)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        text.to_string()
+    } else {
+        let mut cut: String = text.chars().take(max_chars - 1).collect();
+        cut.push('…');
+        cut
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assets::{ModifierCategory, ModifierOption};
+    use crate::object::Modification;
+
+    fn starting() -> ImageRequest {
+        ImageRequest::Starting {
+            goal: "A wizard cat holding A flying pizza slice in outer space".to_string(),
+            objects: vec![GameObject::new("A wizard cat"), GameObject::new("A sentient toaster")],
+        }
+    }
+
+    fn modified(subject: &str, modifiers: &[&str]) -> ImageRequest {
+        let mut object = GameObject::new(subject);
+        for modifier in modifiers {
+            object.apply(&Modification::parse(modifier));
+        }
+        ImageRequest::Starting { goal: "A test goal".to_string(), objects: vec![object] }
+    }
+
+    #[test]
+    fn test_render_is_deterministic() {
+        assert_eq!(render_svg(&starting()), render_svg(&starting()));
+    }
+
+    #[test]
+    fn test_render_contains_labels() {
+        let svg = render_svg(&starting());
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>"));
+        assert!(svg.contains("A wizard cat"));
+        assert!(svg.contains("A sentient toaster"));
+    }
+
+    #[test]
+    fn test_modifiers_add_badges() {
+        let plain = render_svg(&modified("A wizard cat", &[]));
+        let decorated = render_svg(&modified("A wizard cat", &["wearing a cape", "that is glowing green"]));
+        assert_eq!(plain.matches("<polygon").count(), 0);
+        assert_eq!(decorated.matches("<polygon").count(), 2);
+        assert!(decorated.contains("url(#glow)"));
+
+        let rendered = render_svg(&modified("A wizard cat", &[
+            "that is giant", "covered in glitter", "that is glowing green", "wearing a cape", "wearing sunglasses",
+        ]));
+        assert!(rendered.contains("A giant wizard cat"));
+        assert_eq!(rendered.matches("<polygon").count(), 5);
+        // Still drawn as an animal, not a box
+        assert_eq!(rendered.matches("<ellipse").count(), 1);
+    }
+
+    #[test]
+    fn test_subjects_and_goals_get_no_badges() {
+        // Reads like a giant-sized object, but "giant" is part of its name
+        let taco = render_svg(&modified("A giant floating taco", &[]));
+        assert_eq!(taco.matches("<polygon").count(), 0);
+        assert_eq!(render_svg(&modified("A floating taco", &["that is giant"])).matches("<polygon").count(), 1);
+
+        let goal = render_svg(&ImageRequest::Goal {
+            goal: "A wizard cat holding A giant floating taco in outer space".to_string(),
+        });
+        assert_eq!(goal.matches("<polygon").count(), 0);
+        assert_eq!(goal.matches("<ellipse").count(), 1);
+    }
+
+    #[test]
+    fn test_outer_space_is_a_backdrop_for_goals_and_a_badge_for_objects() {
+        // "in outer space" is both a goal location and a Move modifier
+        let (space_top, _) = BACKDROPS[0];
+        let goal = render_svg(&ImageRequest::Goal {
+            goal: "A wizard cat holding A flying pizza slice in outer space".to_string(),
+        });
+        assert!(goal.contains(space_top));
+        assert_eq!(goal.matches("<polygon").count(), 0);
+
+        let option = ModifierOption { category: ModifierCategory::Move, text: "in outer space".to_string() };
+        let mut cat = GameObject::new("A wizard cat");
+        cat.apply(&Modification::of(&option));
+        let moved = render_svg(&ImageRequest::Starting { goal: "A test goal".to_string(), objects: vec![cat] });
+        assert!(moved.contains("A wizard cat in outer space"));
+        assert!(!moved.contains(space_top));
+        assert_eq!(moved.matches("<polygon").count(), 1);
+    }
+
+    #[test]
+    fn test_text_is_escaped() {
+        let svg = render_svg(&ImageRequest::Goal { goal: "A <cat> & 'friends'".to_string() });
+        assert!(svg.contains("&lt;cat&gt; &amp; &apos;friends&apos;"));
+        assert!(!svg.contains("<cat>"));
+    }
+
+    #[test]
+    fn test_generator_writes_file() {
+        let dir = std::env::temp_dir().join(format!("big-picture-images-{}", crate::types::RoomId::new()));
+        let generator = ProceduralImageGenerator::new(&dir);
+
+        let id = generator.generate(&starting()).unwrap();
+        let written = std::fs::read_to_string(dir.join(id.as_str())).unwrap();
+        assert_eq!(written, render_svg(&starting()));
+        assert!(id.as_str().starts_with("start_"));
+        assert!(id.as_str().ends_with(".svg"));
+
+        // Same request maps to the same file
+        assert_eq!(generator.generate(&starting()).unwrap(), id);
+
+        generator.discard(&id).unwrap();
+        assert!(!dir.join(id.as_str()).exists());
+        // Already gone, or never ours: nothing to do
+        generator.discard(&id).unwrap();
+        generator.discard(&ImageId::new("../elsewhere.svg")).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("abcdefghij", 5), "abcd…");
+    }
+}
@@ -1,8 +1,10 @@
 //! Room entity and state management.
 
-use crate::game::GameState;
+use crate::game::{GameStage, GameState};
 use crate::player::Player;
-use crate::types::{PlayerId, RoomId};
+use crate::settings::GameSettings;
+use crate::spectator::Spectator;
+use crate::types::{PlayerId, RoomId, SpectatorId};
 use serde::{Deserialize, Serialize};
 
 /// The state of a game room.
@@ -18,10 +20,28 @@ pub enum RoomState {
     Finished,
 }
 
+/// Someone taking part in a room.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Participant {
+    /// The shared screen (TV) that created the room. It shows the game but
+    /// never takes a seat or a turn.
+    #[default]
+    Display,
+
+    /// One of the room's players.
+    Player { player_id: PlayerId },
+
+    /// An audience member. Spectators never host, take turns or count
+    /// towards the player cap.
+    Spectator { spectator_id: SpectatorId },
+}
+
 /// A game room that contains players and game state.
 ///
 /// Rooms progress through states: Lobby → InGame → Finished.
-/// Players can only join/leave during the Lobby state.
+/// Players can only join/leave during the Lobby state; spectators may come
+/// and go at any time. The display is not a player; it starts as the host.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Room {
     /// Unique identifier for this room.
@@ -30,7 +50,7 @@ pub struct Room {
     /// Human-readable room code for joining (4-6 characters).
     pub code: String,
     
-    /// Players in this room (max 8).
+    /// Players in this room (at most `settings.max_players`).
     pub players: Vec<Player>,
     
     /// Current state of the room.
@@ -38,6 +58,57 @@ pub struct Room {
     
     /// Game state (only present when state is InGame or Finished).
     pub game: Option<GameState>,
+    
+    /// How the next game in this room is played.
+    #[serde(default)]
+    pub settings: GameSettings,
+    
+    /// Who may change settings, start, advance and close the game.
+    #[serde(default)]
+    pub host: Participant,
+    
+    /// Whether the display is currently connected.
+    #[serde(default = "display_connected_default")]
+    pub display_connected: bool,
+    
+    /// Audience members watching the room.
+    #[serde(default)]
+    pub spectators: Vec<Spectator>,
+    
+    /// When the room last changed (Unix seconds); 0 if unknown.
+    #[serde(default)]
+    pub last_activity: u64,
+    
+    /// Seed the room's game is drawn from, when fixed at creation.
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+/// How long a room may go without activity before it expires, by stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdleTimeouts {
+    /// Seconds for rooms still in the lobby, including ones nobody joined.
+    pub lobby: u64,
+
+    /// Seconds for games in progress.
+    pub in_game: u64,
+
+    /// Seconds for games showing their results, or finished.
+    pub finished: u64,
+}
+
+impl Default for IdleTimeouts {
+    fn default() -> Self {
+        Self {
+            lobby: 30 * 60,
+            in_game: 30 * 60,
+            finished: 10 * 60,
+        }
+    }
+}
+
+fn display_connected_default() -> bool {
+    true
 }
 
 impl Room {
@@ -49,6 +120,12 @@ impl Room {
             players: Vec::new(),
             state: RoomState::Lobby,
             game: None,
+            settings: GameSettings::default(),
+            host: Participant::Display,
+            display_connected: true,
+            spectators: Vec::new(),
+            last_activity: 0,
+            seed: None,
         }
     }
 
@@ -57,15 +134,14 @@ impl Room {
         self.players.len()
     }
 
-    /// Check if the room is full (8 players).
+    /// Check if the room has reached `settings.max_players`.
     pub fn is_full(&self) -> bool {
-        self.players.len() >= 8
+        self.players.len() >= self.settings.max_players
     }
 
-    /// Check if the room has enough players to start (2-8).
+    /// Check if the player count is within the settings' range to start.
     pub fn can_start(&self) -> bool {
-        let count = self.players.len();
-        count >= 2 && count <= 8
+        self.settings.player_range().contains(&self.players.len())
     }
 
     /// Check if a player with the given nickname exists in this room.
@@ -73,6 +149,93 @@ impl Room {
         self.players.iter().any(|p| p.matches_nickname(nickname))
     }
 
+    /// Check if `participant` holds host privileges.
+    pub fn is_host(&self, participant: Participant) -> bool {
+        self.host == participant
+    }
+
+    /// Check if a participant is still in the room and connected.
+    ///
+    /// Spectators' presence is not tracked: they count as connected until
+    /// they leave.
+    pub fn is_connected(&self, participant: Participant) -> bool {
+        match participant {
+            Participant::Display => self.display_connected,
+            Participant::Player { player_id } => self.find_player(player_id).is_some_and(|p| p.connected),
+            Participant::Spectator { spectator_id } => self.find_spectator(spectator_id).is_some(),
+        }
+    }
+
+    /// Mark the display or a player as (re)connected at `at` or disconnected.
+    ///
+    /// Spectators are left as they are. Returns false if the player or
+    /// spectator is not in the room.
+    pub fn set_connected(&mut self, participant: Participant, connected: bool, at: u64) -> bool {
+        match participant {
+            Participant::Display => self.display_connected = connected,
+            Participant::Spectator { spectator_id } => return self.find_spectator(spectator_id).is_some(),
+            Participant::Player { player_id } => {
+                let Some(player) = self.find_player_mut(player_id) else {
+                    return false;
+                };
+                if connected {
+                    player.reconnect();
+                    player.connected_since = at;
+                } else {
+                    player.disconnect();
+                }
+            }
+        }
+        true
+    }
+
+    /// Who should hold host privileges now, or `None` if nobody is left.
+    ///
+    /// A connected display always hosts. Otherwise a connected host keeps the
+    /// role, or it passes to the player connected the longest (earliest
+    /// joined on ties). With everyone disconnected a remaining host player
+    /// keeps it, or else the first remaining player.
+    pub fn elect_host(&self) -> Option<Participant> {
+        if self.display_connected {
+            return Some(Participant::Display);
+        }
+        if self.is_connected(self.host) {
+            return Some(self.host);
+        }
+        let as_participant = |p: &Player| Participant::Player { player_id: p.id };
+        let longest_connected = self.players.iter()
+            .filter(|p| p.connected)
+            .min_by_key(|p| p.connected_since);
+        if let Some(player) = longest_connected {
+            return Some(as_participant(player));
+        }
+        if let Participant::Player { player_id } = self.host {
+            if self.find_player(player_id).is_some() {
+                return Some(self.host);
+            }
+        }
+        self.players.first().map(as_participant)
+    }
+
+    /// Check whether the room has gone without activity for longer than
+    /// its stage allows at `now`.
+    ///
+    /// Until results are shown, a room with the display or a player still
+    /// connected is waiting rather than abandoned, and never idle.
+    pub fn is_idle(&self, now: u64, timeouts: &IdleTimeouts) -> bool {
+        let showing_results = self.game.as_ref().is_some_and(|game| game.stage == GameStage::Results);
+        let over = showing_results || self.state == RoomState::Finished;
+        if !over && (self.display_connected || self.players.iter().any(|p| p.connected)) {
+            return false;
+        }
+        let timeout = match self.state {
+            RoomState::Lobby => timeouts.lobby,
+            RoomState::InGame if !showing_results => timeouts.in_game,
+            RoomState::InGame | RoomState::Finished => timeouts.finished,
+        };
+        now.saturating_sub(self.last_activity) > timeout
+    }
+
     /// Find a player by ID.
     pub fn find_player(&self, player_id: PlayerId) -> Option<&Player> {
         self.players.iter().find(|p| p.id == player_id)
@@ -97,12 +260,47 @@ impl Room {
         id
     }
 
-    /// Remove a player from the room by ID.
+    /// Remove a player from the room by ID, skipping their turns in a game
+    /// in progress.
     ///
     /// Returns true if the player was found and removed.
     pub fn remove_player(&mut self, player_id: PlayerId) -> bool {
         if let Some(pos) = self.players.iter().position(|p| p.id == player_id) {
             self.players.remove(pos);
+            if let Some(game) = self.game.as_mut() {
+                game.remove_player(player_id);
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Check if a spectator with the given nickname is watching.
+    pub fn has_spectator_with_nickname(&self, nickname: &str) -> bool {
+        self.spectators.iter().any(|s| s.matches_nickname(nickname))
+    }
+
+    /// Find a spectator by ID.
+    pub fn find_spectator(&self, spectator_id: SpectatorId) -> Option<&Spectator> {
+        self.spectators.iter().find(|s| s.id == spectator_id)
+    }
+
+    /// Add a spectator to the room at any stage.
+    ///
+    /// Returns the spectator's ID.
+    pub fn add_spectator(&mut self, spectator: Spectator) -> SpectatorId {
+        let id = spectator.id;
+        self.spectators.push(spectator);
+        id
+    }
+
+    /// Remove a spectator from the room by ID.
+    ///
+    /// Returns true if the spectator was found and removed.
+    pub fn remove_spectator(&mut self, spectator_id: SpectatorId) -> bool {
+        if let Some(pos) = self.spectators.iter().position(|s| s.id == spectator_id) {
+            self.spectators.remove(pos);
             true
         } else {
             false
@@ -112,10 +310,10 @@ impl Room {
     /// Transition the room to the InGame state.
     ///
     /// This should only be called when the room is in Lobby state
-    /// and has 2-8 players.
+    /// and `can_start` holds.
     pub fn start_game(&mut self, game_state: GameState) {
         debug_assert!(self.state == RoomState::Lobby, "Can only start from Lobby");
-        debug_assert!(self.can_start(), "Need 2-8 players to start");
+        debug_assert!(self.can_start(), "Player count outside the settings' range");
         
         self.state = RoomState::InGame;
         self.game = Some(game_state);
@@ -300,4 +498,103 @@ mod tests {
         assert!(!room.has_player_with_nickname("ALICE"));
         assert!(room.has_player_with_nickname("Alice"));
     }
+
+    #[test]
+    fn test_elect_host_prefers_connected_display() {
+        let mut room = Room::new("HOST01".to_string());
+        let alice = room.add_player(create_test_player("Alice"));
+        assert_eq!(room.elect_host(), Some(Participant::Display));
+        
+        room.display_connected = false;
+        assert_eq!(room.elect_host(), Some(Participant::Player { player_id: alice }));
+    }
+
+    #[test]
+    fn test_elect_host_picks_longest_connected_player() {
+        let mut room = Room::new("HOST02".to_string());
+        room.display_connected = false;
+        let mut alice = create_test_player("Alice");
+        alice.connected_since = 200;
+        let alice = room.add_player(alice);
+        let mut bob = create_test_player("Bob");
+        bob.connected_since = 100;
+        let bob = room.add_player(bob);
+        
+        assert_eq!(room.elect_host(), Some(Participant::Player { player_id: bob }));
+        
+        // A connected host keeps the role
+        room.host = Participant::Player { player_id: alice };
+        assert_eq!(room.elect_host(), Some(Participant::Player { player_id: alice }));
+        
+        room.find_player_mut(alice).unwrap().disconnect();
+        assert_eq!(room.elect_host(), Some(Participant::Player { player_id: bob }));
+        
+        // Everyone gone: the host keeps the role while still in the room
+        room.find_player_mut(bob).unwrap().disconnect();
+        assert_eq!(room.elect_host(), Some(Participant::Player { player_id: alice }));
+        
+        room.remove_player(alice);
+        room.remove_player(bob);
+        assert_eq!(room.elect_host(), None);
+    }
+
+    #[test]
+    fn test_spectators_take_no_seat() {
+        let mut room = Room::new("AUDI01".to_string());
+        room.settings.max_players = 2;
+        room.add_player(create_test_player("Alice"));
+        room.add_player(create_test_player("Bob"));
+        let dana = room.add_spectator(Spectator::new("Dana".to_string()));
+        
+        assert!(room.is_full());
+        assert_eq!(room.player_count(), 2);
+        assert!(room.can_start());
+        assert!(room.has_spectator_with_nickname("Dana"));
+        assert!(!room.has_player_with_nickname("Dana"));
+        
+        let spectator = Participant::Spectator { spectator_id: dana };
+        assert!(room.is_connected(spectator));
+        
+        // With the display and every player gone, a spectator never hosts
+        room.display_connected = false;
+        room.players.clear();
+        assert_eq!(room.elect_host(), None);
+        
+        assert!(room.remove_spectator(dana));
+        assert!(!room.remove_spectator(dana));
+        assert!(!room.is_connected(spectator));
+    }
+
+    #[test]
+    fn test_idle_timeout_depends_on_stage() {
+        let timeouts = IdleTimeouts { lobby: 100, in_game: 300, finished: 50 };
+        let mut room = Room::new("IDLE01".to_string());
+        room.last_activity = 1000;
+        room.display_connected = false;
+        assert!(!room.is_idle(1100, &timeouts));
+        assert!(room.is_idle(1101, &timeouts));
+        
+        let player = room.add_player(create_test_player("Alice"));
+        room.add_player(create_test_player("Bob"));
+        // Players waiting in the lobby keep it open
+        assert!(!room.is_idle(1101, &timeouts));
+        room.players.iter_mut().for_each(|p| p.connected = false);
+        let mut game = GameState::new(
+            ImageId::new("goal"),
+            "Goal".to_string(),
+            ImageId::new("start"),
+            std::collections::HashMap::new(),
+            vec![player],
+            1,
+        );
+        room.start_game(game.clone());
+        assert!(!room.is_idle(1101, &timeouts));
+        assert!(room.is_idle(1301, &timeouts));
+        
+        game.stage = GameStage::Results;
+        room.game = Some(game);
+        // Results close on time even with everyone still looking
+        room.display_connected = true;
+        assert!(room.is_idle(1051, &timeouts));
+    }
 }
@@ -1,44 +1,190 @@
 //! Room management logic for handling multiple game sessions.
 
-use std::collections::HashMap;
-use rand::{distributions::Alphanumeric, Rng};
-use crate::errors::RoomError;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use rand::rngs::StdRng;
+use rand::{distributions::Alphanumeric, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use crate::content::ContentPack;
+use crate::errors::{ContentError, RoomError};
+use crate::events::RoomEvent;
+use crate::game_log::{unix_now, Draw, GameEvent, LoggedEvent};
+use crate::game::{Expiry, GameStage, GameState};
+use crate::image_generator::{ImageGenerator, ImageRequest, PlaceholderImageGenerator};
+use crate::object::GameObject;
 use crate::player::Player;
-use crate::room::{Room, RoomState};
-use crate::types::{AvatarId, PlayerId, RoomId};
+use crate::rng::GameRng;
+use crate::room::{IdleTimeouts, Participant, Room, RoomState};
+use crate::session::{Rejoin, Session, SessionToken};
+use crate::settings::GameSettings;
+use crate::spectator::Spectator;
+use crate::types::{AvatarId, ImageId, PlayerId, RoomId, SpectatorId};
+
+/// Every room and room code, saved so games survive a server restart.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoomsSnapshot {
+    /// Map of room codes to Room IDs.
+    pub code_to_id: HashMap<String, RoomId>,
+    
+    /// All rooms, including games in progress.
+    pub rooms: Vec<Room>,
+    
+    /// Event log of each room.
+    #[serde(default)]
+    pub logs: HashMap<RoomId, Vec<LoggedEvent>>,
+    
+    /// Live session tokens, so clients stay signed in across a restart.
+    #[serde(default)]
+    pub sessions: HashMap<SessionToken, Session>,
+}
 
 /// Manages active game rooms and player sessions.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct RoomManager {
     /// Map of room codes to Room IDs.
     code_to_id: HashMap<String, RoomId>,
     
     /// Map of Room IDs to Room instances.
     rooms: HashMap<RoomId, Room>,
+    
+    /// Backend used to draw goal, starting and per-turn images.
+    image_generator: Arc<dyn ImageGenerator>,
+    
+    /// Settings given to newly created rooms.
+    default_settings: GameSettings,
+    
+    /// Decks (content packs) rooms can choose from.
+    decks: Vec<ContentPack>,
+    
+    /// Change notifications not yet drained by `take_events`.
+    events: Vec<(RoomId, RoomEvent)>,
+    
+    /// Every mutation of each room, for replay.
+    logs: HashMap<RoomId, Vec<LoggedEvent>>,
+    
+    /// Secret tokens of displays and players.
+    sessions: HashMap<SessionToken, Session>,
+    
+    /// When each display and player was last heard from (Unix seconds).
+    ///
+    /// Not saved: after a restore everyone gets a fresh grace period.
+    last_seen: HashMap<(RoomId, Participant), u64>,
+}
+
+impl Default for RoomManager {
+    fn default() -> Self {
+        Self::with_image_generator(Arc::new(PlaceholderImageGenerator))
+    }
 }
 
 impl RoomManager {
-    /// Create a new empty RoomManager.
+    /// Create a new empty RoomManager using placeholder images.
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Create a new empty RoomManager that draws images with `image_generator`.
+    pub fn with_image_generator(image_generator: Arc<dyn ImageGenerator>) -> Self {
+        Self {
+            code_to_id: HashMap::new(),
+            rooms: HashMap::new(),
+            image_generator,
+            default_settings: GameSettings::default(),
+            decks: vec![ContentPack::builtin()],
+            events: Vec::new(),
+            logs: HashMap::new(),
+            sessions: HashMap::new(),
+            last_seen: HashMap::new(),
+        }
+    }
+
+    /// Set the settings given to rooms created from now on.
+    pub fn set_default_settings(&mut self, settings: GameSettings) -> Result<(), RoomError> {
+        settings.validate()?;
+        check_decks(&self.decks, &settings.decks)?;
+        self.default_settings = settings;
+        Ok(())
+    }
+
+    /// Set the decks rooms can choose from.
+    ///
+    /// The built-in deck stays available unless one with its id replaces it.
+    /// Games already in progress keep the content they started with.
+    pub fn set_decks(&mut self, decks: Vec<ContentPack>) -> Result<(), ContentError> {
+        let mut ids = HashSet::new();
+        for deck in &decks {
+            deck.validate()?;
+            if !ids.insert(deck.id.as_str()) {
+                return Err(ContentError::DuplicatePack(deck.id.clone()));
+            }
+        }
+        let builtin = ContentPack::builtin();
+        let keep_builtin = !ids.contains(builtin.id.as_str());
+        self.decks = keep_builtin.then_some(builtin).into_iter().chain(decks).collect();
+        Ok(())
+    }
+
+    /// Decks rooms can choose from.
+    pub fn decks(&self) -> &[ContentPack] {
+        &self.decks
+    }
+
+    /// Drain the change notifications queued by mutations since the last call.
+    pub fn take_events(&mut self) -> Vec<(RoomId, RoomEvent)> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Every recorded mutation of a room, oldest first.
+    pub fn game_log(&self, room_id: &RoomId) -> Option<&[LoggedEvent]> {
+        self.logs.get(room_id).map(Vec::as_slice)
+    }
+
+    /// Get the configured image generator.
+    pub fn image_generator(&self) -> &Arc<dyn ImageGenerator> {
+        &self.image_generator
+    }
+
     /// Create a new room with a unique random code.
     ///
     /// Returns the RoomId and the generated room code.
     pub fn create_room(&mut self) -> (RoomId, String) {
-        let code = self.generate_unique_code();
-        let room = Room::new(code.clone());
+        let code = self.generate_unique_code(&mut rand::thread_rng());
+        self.insert_room(code, None)
+    }
+
+    /// Create a room whose code and game are drawn from `seed`, so the same
+    /// seed reproduces the same goal, starting objects and options (for
+    /// tests and bug reports).
+    pub fn create_seeded_room(&mut self, seed: u64) -> (RoomId, String) {
+        let code = self.generate_unique_code(&mut StdRng::seed_from_u64(seed));
+        self.insert_room(code, Some(seed))
+    }
+
+    fn insert_room(&mut self, code: String, seed: Option<u64>) -> (RoomId, String) {
+        let mut room = Room::new(code.clone());
+        room.settings = self.default_settings.clone();
+        room.seed = seed;
         let id = room.id;
         
         self.code_to_id.insert(code.clone(), id);
         self.rooms.insert(id, room);
+        self.last_seen.insert((id, Participant::Display), unix_now());
+        self.record(id, GameEvent::RoomCreated {
+            room_id: id,
+            code: code.clone(),
+            settings: self.default_settings.clone(),
+            seed,
+        });
         
         (id, code)
     }
 
     /// Join a room using a room code.
     ///
+    /// Once the game has started this only works while it accepts late
+    /// joins (see [`GameState::accepts_late_joins`]); the new player gets a
+    /// starting object and their first turn next round.
+    ///
     /// Returns the RoomId and PlayerId if successful.
     pub fn join_room(
         &mut self,
@@ -53,7 +199,8 @@ impl RoomManager {
         let room = self.rooms.get_mut(&room_id)
             .ok_or_else(|| RoomError::NotFound(code.to_string()))?;
             
-        if room.state != RoomState::Lobby {
+        let late = room.state != RoomState::Lobby;
+        if late && !room.game.as_ref().is_some_and(GameState::accepts_late_joins) {
             return Err(RoomError::AlreadyStarted(room_id));
         }
         
@@ -65,18 +212,89 @@ impl RoomManager {
             return Err(RoomError::NicknameTaken(nickname, room_id));
         }
         
-        let player = Player::new(nickname, avatar_id);
+        // Draw before changing anything, so a game out of starting objects
+        // turns the player away without a trace
+        let starting_object = match room.game.as_mut().filter(|_| late) {
+            Some(game) => Some(game.draw_late_object().ok_or(RoomError::Full(room_id))?),
+            None => None,
+        };
+        
+        let now = unix_now();
+        let mut player = Player::new(nickname.clone(), avatar_id);
+        player.connected_since = now;
         let player_id = player.id;
         room.add_player(player);
+        self.last_seen.insert((room_id, Participant::Player { player_id }), now);
+        
+        self.record_at(room_id, now, GameEvent::PlayerJoined { player_id, nickname: nickname.clone(), avatar_id });
+        self.events.push((room_id, RoomEvent::PlayerJoined { player_id, nickname, avatar_id }));
+        if let Some(starting_object) = starting_object {
+            self.join_game_late(room_id, player_id, starting_object)?;
+        }
         
         Ok((room_id, player_id))
     }
 
-    /// Rejoin a room if a player was disconnected.
+    /// Seat a player who joined mid-game with the starting object drawn for them.
+    fn join_game_late(&mut self, room_id: RoomId, player_id: PlayerId, starting_object: String) -> Result<(), RoomError> {
+        let record = |_: &GameState| GameEvent::LateJoined { player_id, starting_object: starting_object.clone() };
+        self.update_game(&room_id, None, record, |game, _, _| {
+            game.add_late_player(player_id, starting_object.clone())
+        })
+    }
+
+    /// Start watching a room as a spectator.
+    ///
+    /// Works in any room state and regardless of the player cap. Returns the
+    /// RoomId and SpectatorId if successful.
+    pub fn join_as_spectator(
+        &mut self,
+        code: &str,
+        nickname: String,
+    ) -> Result<(RoomId, SpectatorId), RoomError> {
+        let room_id = self.code_to_id.get(code)
+            .copied()
+            .ok_or_else(|| RoomError::InvalidCode(code.to_string()))?;
+            
+        let room = self.rooms.get_mut(&room_id)
+            .ok_or_else(|| RoomError::NotFound(code.to_string()))?;
+        
+        if room.has_spectator_with_nickname(&nickname) {
+            return Err(RoomError::NicknameTaken(nickname, room_id));
+        }
+        
+        let spectator_id = room.add_spectator(Spectator::new(nickname.clone()));
+        self.record(room_id, GameEvent::SpectatorJoined { spectator_id, nickname: nickname.clone() });
+        self.events.push((room_id, RoomEvent::SpectatorJoined { spectator_id, nickname }));
+        
+        Ok((room_id, spectator_id))
+    }
+
+    /// Stop watching a room, signing out the spectator's session.
+    pub fn leave_as_spectator(&mut self, room_id: RoomId, spectator_id: SpectatorId) -> Result<(), RoomError> {
+        let room = self.rooms.get_mut(&room_id)
+            .ok_or_else(|| RoomError::NotFound(room_id.to_string()))?;
+        
+        if !room.remove_spectator(spectator_id) {
+            return Err(RoomError::SpectatorNotFound(spectator_id, room_id));
+        }
+        self.record(room_id, GameEvent::SpectatorLeft { spectator_id });
+        self.events.push((room_id, RoomEvent::SpectatorLeft { spectator_id }));
+        let participant = Participant::Spectator { spectator_id };
+        self.sessions.retain(|_, session| session.participant != participant);
+        self.last_seen.remove(&(room_id, participant));
+        Ok(())
+    }
+
+    /// Rejoin a room as an existing player.
+    ///
+    /// The player keeps their ID, so their seat in turn order, objects and
+    /// votes are exactly as they left them. The caller should issue a new
+    /// session, which signs out the old token.
     pub fn rejoin_room(
         &mut self,
         code: &str,
-        nickname: &str,
+        credentials: Rejoin,
     ) -> Result<(RoomId, PlayerId), RoomError> {
         let room_id = self.code_to_id.get(code)
             .copied()
@@ -84,40 +302,310 @@ impl RoomManager {
             
         let room = self.rooms.get_mut(&room_id)
             .ok_or_else(|| RoomError::NotFound(code.to_string()))?;
-            
-        let player = room.find_player_by_nickname(nickname)
-            .ok_or_else(|| RoomError::NicknameTaken(nickname.to_string(), room_id))?; // Reusing error for "not found" in this context
-            
-        let player_id = player.id;
         
-        // In a real app, we'd mark them as connected here
-        if let Some(p) = room.find_player_mut(player_id) {
-            p.reconnect();
+        let player_id = match credentials {
+            Rejoin::ResumeToken(token) => {
+                let session = self.sessions.get(&token)
+                    .filter(|session| session.room_id == room_id)
+                    .ok_or(RoomError::Unauthorized)?;
+                let Participant::Player { player_id } = session.participant else {
+                    return Err(RoomError::Forbidden("only players can rejoin".to_string()));
+                };
+                room.find_player(player_id)
+                    .ok_or(RoomError::PlayerNotFound(player_id, room_id))?
+                    .id
+            }
+            Rejoin::Nickname(nickname) => {
+                let player = room.find_player_by_nickname(&nickname)
+                    .ok_or_else(|| RoomError::NicknameNotFound(nickname.clone(), room_id))?;
+                if player.connected {
+                    return Err(RoomError::Forbidden(format!(
+                        "'{}' is still connected; rejoin with your resume token",
+                        nickname
+                    )));
+                }
+                player.id
+            }
+        };
+        
+        let participant = Participant::Player { player_id };
+        let was_connected = room.is_connected(participant);
+        let now = unix_now();
+        room.set_connected(participant, true, now);
+        self.last_seen.insert((room_id, participant), now);
+        self.record_at(room_id, now, GameEvent::PlayerReconnected { player_id });
+        if !was_connected {
+            self.events.push((room_id, RoomEvent::ConnectionChanged { participant, connected: true }));
         }
+        self.sync_presence(room_id);
+        self.update_host(room_id);
         
         Ok((room_id, player_id))
     }
 
     /// Leave a room.
+    ///
+    /// A leaving host hands the role on; the room closes once neither the
+    /// display nor any player is left.
     pub fn leave_room(&mut self, room_id: RoomId, player_id: PlayerId) -> Result<(), RoomError> {
         let room = self.rooms.get_mut(&room_id)
             .ok_or_else(|| RoomError::NotFound(room_id.to_string()))?;
             
-        let is_host = room.players.first().map(|p| p.id == player_id).unwrap_or(false);
-
         if room.remove_player(player_id) {
-            // If room is empty OR the host left, remove the room
-            if room.player_count() == 0 || is_host {
-                let code = room.code.clone();
-                self.code_to_id.remove(&code);
-                self.rooms.remove(&room_id);
-            }
+            self.record(room_id, GameEvent::PlayerLeft { player_id });
+            self.events.push((room_id, RoomEvent::PlayerLeft { player_id }));
+            let participant = Participant::Player { player_id };
+            self.sessions.retain(|_, session| session.participant != participant);
+            self.last_seen.remove(&(room_id, participant));
+            self.sync_presence(room_id);
+            self.update_host(room_id);
             Ok(())
         } else {
             Err(RoomError::PlayerNotFound(player_id, room_id))
         }
     }
 
+    /// Close a room for everyone, signing out all of its sessions and
+    /// deleting the images only it showed.
+    pub fn close_room(&mut self, room_id: RoomId) -> Result<(), RoomError> {
+        let room = self.rooms.remove(&room_id)
+            .ok_or_else(|| RoomError::NotFound(room_id.to_string()))?;
+        self.discard_images(&room);
+        self.code_to_id.remove(&room.code);
+        self.logs.remove(&room_id);
+        self.sessions.retain(|_, session| session.room_id != room_id);
+        self.last_seen.retain(|(id, _), _| *id != room_id);
+        self.events.push((room_id, RoomEvent::RoomClosed));
+        Ok(())
+    }
+
+    /// Delete the images of a closed room that no open room shows.
+    ///
+    /// Images are named after their content, so rooms drawing the same goal
+    /// share an image file.
+    fn discard_images(&self, closed: &Room) {
+        let Some(game) = &closed.game else {
+            return;
+        };
+        let in_use: HashSet<&ImageId> = self.rooms.values()
+            .filter_map(|room| room.game.as_ref())
+            .flat_map(GameState::images)
+            .collect();
+        let unused: HashSet<&ImageId> = game.images().filter(|image| !in_use.contains(image)).collect();
+        for image in unused {
+            // The room is gone either way; a file left behind only wastes space
+            let _ = self.image_generator.discard(image);
+        }
+    }
+
+    /// Close every room left without activity for longer than `timeouts`
+    /// allow at `now`, telling its clients why.
+    ///
+    /// Returns the expired rooms. Rooms of unknown age (such as those saved
+    /// before activity was tracked) start counting now.
+    pub fn expire_idle_rooms(&mut self, now: u64, timeouts: &IdleTimeouts) -> Vec<RoomId> {
+        let mut idle = Vec::new();
+        for room in self.rooms.values_mut() {
+            if room.last_activity == 0 {
+                room.last_activity = now;
+            }
+            if room.is_idle(now, timeouts) {
+                idle.push(room.id);
+            }
+        }
+        
+        for room_id in &idle {
+            self.events.push((*room_id, RoomEvent::RoomExpired));
+            // Cannot fail: the room was just found
+            let _ = self.close_room(*room_id);
+        }
+        idle
+    }
+
+    /// Mark the display or a player as disconnected.
+    ///
+    /// A disconnected host hands the role to the player connected the longest.
+    pub fn disconnect(&mut self, room_id: RoomId, participant: Participant) -> Result<(), RoomError> {
+        self.set_connected(room_id, participant, false, unix_now())
+    }
+
+    /// Mark the display or a player as connected again.
+    ///
+    /// A returning display takes host privileges back.
+    pub fn reconnect(&mut self, room_id: RoomId, participant: Participant) -> Result<(), RoomError> {
+        self.set_connected(room_id, participant, true, unix_now())
+    }
+
+    /// Note that the display or a player was heard from at `now`.
+    ///
+    /// Reconnects them if they had been marked disconnected.
+    pub fn touch(&mut self, room_id: RoomId, participant: Participant, now: u64) -> Result<(), RoomError> {
+        let room = self.rooms.get(&room_id)
+            .ok_or_else(|| RoomError::NotFound(room_id.to_string()))?;
+        check_member(room, participant)?;
+        
+        let was_connected = room.is_connected(participant);
+        self.last_seen.insert((room_id, participant), now);
+        if !was_connected {
+            self.set_connected(room_id, participant, true, now)?;
+        }
+        Ok(())
+    }
+
+    /// Disconnect everyone not heard from in the last `grace` seconds.
+    ///
+    /// Returns who was disconnected. Participants never heard from (such as
+    /// those restored from a snapshot) start their grace period now.
+    pub fn sweep_presence(&mut self, now: u64, grace: u64) -> Vec<(RoomId, Participant)> {
+        let mut stale = Vec::new();
+        for room in self.rooms.values() {
+            let participants = std::iter::once(Participant::Display)
+                .chain(room.players.iter().map(|p| Participant::Player { player_id: p.id }));
+            for participant in participants {
+                if !room.is_connected(participant) {
+                    continue;
+                }
+                let last_seen = *self.last_seen.entry((room.id, participant)).or_insert(now);
+                if now.saturating_sub(last_seen) > grace {
+                    stale.push((room.id, participant));
+                }
+            }
+        }
+        
+        for (room_id, participant) in &stale {
+            // The room may have closed when an earlier participant went
+            let _ = self.set_connected(*room_id, *participant, false, now);
+        }
+        stale
+    }
+
+    fn set_connected(&mut self, room_id: RoomId, participant: Participant, connected: bool, now: u64) -> Result<(), RoomError> {
+        let room = self.rooms.get_mut(&room_id)
+            .ok_or_else(|| RoomError::NotFound(room_id.to_string()))?;
+        
+        check_member(room, participant)?;
+        if matches!(participant, Participant::Spectator { .. }) {
+            // The audience's presence is not tracked
+            return Ok(());
+        }
+        
+        room.set_connected(participant, connected, now);
+        if connected {
+            self.last_seen.insert((room_id, participant), now);
+        }
+        
+        let event = if connected {
+            GameEvent::Reconnected { participant }
+        } else {
+            GameEvent::Disconnected { participant }
+        };
+        self.record_at(room_id, now, event);
+        self.events.push((room_id, RoomEvent::ConnectionChanged { participant, connected }));
+        if matches!(participant, Participant::Player { .. }) {
+            self.sync_presence(room_id);
+        }
+        self.update_host(room_id);
+        Ok(())
+    }
+
+    /// Let a game in progress skip or resume turns for who is connected now.
+    fn sync_presence(&mut self, room_id: RoomId) {
+        let in_progress = self.rooms.get(&room_id)
+            .and_then(|room| room.game.as_ref())
+            .is_some_and(|game| game.stage != GameStage::Results);
+        if !in_progress {
+            return;
+        }
+        let record = |game: &GameState| GameEvent::PresenceSynced { draw: Draw::of(game) };
+        // Cannot fail: the game exists and syncing always succeeds
+        let _ = self.update_game(&room_id, None, record, |game, connected, _| {
+            game.set_connected_players(connected);
+            Ok(())
+        });
+    }
+
+    /// Hand host privileges to whoever should hold them now, closing the
+    /// room if nobody is left.
+    fn update_host(&mut self, room_id: RoomId) {
+        let Some(room) = self.rooms.get_mut(&room_id) else {
+            return;
+        };
+        match room.elect_host() {
+            Some(host) if host != room.host => {
+                room.host = host;
+                self.record(room_id, GameEvent::HostChanged { host });
+                self.events.push((room_id, RoomEvent::HostChanged { host }));
+            }
+            Some(_) => {}
+            None => {
+                let _ = self.close_room(room_id);
+            }
+        }
+    }
+
+    /// Issue a session token to a room's display, one of its players or a
+    /// spectator.
+    ///
+    /// Any earlier token of the same participant stops working, so a rejoin
+    /// signs out the old device.
+    pub fn issue_session(
+        &mut self,
+        room_id: RoomId,
+        participant: Participant,
+    ) -> Result<SessionToken, RoomError> {
+        let room = self.rooms.get(&room_id)
+            .ok_or_else(|| RoomError::NotFound(room_id.to_string()))?;
+        check_member(room, participant)?;
+        
+        let session = Session { room_id, participant };
+        self.sessions.retain(|_, existing| *existing != session);
+        let token = SessionToken::generate();
+        self.sessions.insert(token.clone(), session);
+        Ok(token)
+    }
+
+    /// Look up the session a token grants in a room.
+    pub fn authenticate(&self, room_id: &RoomId, token: &SessionToken) -> Result<Session, RoomError> {
+        self.sessions.get(token)
+            .filter(|session| session.room_id == *room_id)
+            .copied()
+            .ok_or(RoomError::Unauthorized)
+    }
+
+    /// Look up the player a token belongs to.
+    ///
+    /// Fails with `Forbidden` for the display's or a spectator's token.
+    pub fn authenticate_player(&self, room_id: &RoomId, token: &SessionToken) -> Result<PlayerId, RoomError> {
+        match self.authenticate(room_id, token)?.participant {
+            Participant::Player { player_id } => Ok(player_id),
+            Participant::Display | Participant::Spectator { .. } => {
+                Err(RoomError::Forbidden("only players can do this".to_string()))
+            }
+        }
+    }
+
+    /// Look up the spectator a token belongs to.
+    ///
+    /// Fails with `Forbidden` for anyone else's token.
+    pub fn authenticate_spectator(&self, room_id: &RoomId, token: &SessionToken) -> Result<SpectatorId, RoomError> {
+        match self.authenticate(room_id, token)?.participant {
+            Participant::Spectator { spectator_id } => Ok(spectator_id),
+            _ => Err(RoomError::Forbidden("only spectators can do this".to_string())),
+        }
+    }
+
+    /// Check that a token belongs to the room's host.
+    pub fn authorize_host(&self, room_id: &RoomId, token: &SessionToken) -> Result<(), RoomError> {
+        let session = self.authenticate(room_id, token)?;
+        let room = self.rooms.get(room_id)
+            .ok_or_else(|| RoomError::NotFound(room_id.to_string()))?;
+        if room.is_host(session.participant) {
+            Ok(())
+        } else {
+            Err(RoomError::Forbidden("only the host can do this".to_string()))
+        }
+    }
+
     /// Start the game in a room.
     pub fn start_game(&mut self, room_id: &RoomId) -> Result<(), RoomError> {
         let room = self.rooms.get_mut(room_id)
@@ -128,36 +616,202 @@ impl RoomManager {
         }
         
         if !room.can_start() {
-            return Err(RoomError::NotEnoughPlayers(*room_id));
+            return Err(RoomError::NotEnoughPlayers(*room_id, room.settings.min_players));
         }
         
         // Create initial game state
         let player_ids: Vec<PlayerId> = room.players.iter().map(|p| p.id).collect();
-        let (communal_goal, player_objects) = crate::assets::generate_game_assets(player_ids.len());
+        let content = selected_content(&self.decks, &room.settings.decks)?;
+        let mut rng = room.seed.map(GameRng::new).unwrap_or_else(GameRng::from_entropy);
+        let (communal_goal, player_objects) =
+            crate::assets::generate_game_assets(&content, player_ids.len(), &mut rng.next_draw());
+        
+        let goal_image = self.image_generator
+            .generate(&ImageRequest::Goal { goal: communal_goal.clone() })
+            .map_err(|e| RoomError::ImageGeneration(e.to_string()))?;
+        let starting_image = self.image_generator
+            .generate(&ImageRequest::Starting {
+                goal: communal_goal.clone(),
+                objects: player_objects.iter().map(GameObject::new).collect(),
+            })
+            .map_err(|e| RoomError::ImageGeneration(e.to_string()))?;
         
         let mut player_starting_objects = std::collections::HashMap::new();
         for (id, obj) in player_ids.iter().zip(player_objects) {
             player_starting_objects.insert(*id, obj);
         }
-
-        // In a real game, these would be generated by an AI service
-        let goal_image = crate::types::ImageId::new("goal_placeholder");
-        let starting_image = crate::types::ImageId::new("start_placeholder");
         
-        let game_state = crate::game::GameState::new(
+        let mut game_state = crate::game::GameState::new(
             goal_image,
             communal_goal,
             starting_image,
             player_starting_objects,
             player_ids,
-            3, // 3 rounds
-        );
+            room.settings.rounds,
+        )
+        .with_settings(room.settings.clone())
+        .with_content(content)
+        .with_rng(rng);
+        let connected: Vec<PlayerId> = room.players.iter()
+            .filter(|p| p.connected)
+            .map(|p| p.id)
+            .collect();
+        game_state.set_connected_players(&connected);
         
+        let deadline = game_state.stage_deadline();
+        let game = Box::new(game_state.clone());
         room.start_game(game_state);
         
+        self.record(*room_id, GameEvent::GameStarted { game });
+        self.events.push((*room_id, RoomEvent::GameStarted));
+        self.events.push((*room_id, RoomEvent::StageChanged { stage: GameStage::RevealGoal, deadline }));
+        
+        Ok(())
+    }
+
+    /// Change a room's game settings. Only allowed in the lobby.
+    pub fn update_settings(&mut self, room_id: &RoomId, settings: GameSettings) -> Result<(), RoomError> {
+        let room = self.rooms.get_mut(room_id)
+            .ok_or_else(|| RoomError::NotFound(room_id.to_string()))?;
+        
+        if room.state != RoomState::Lobby {
+            return Err(RoomError::AlreadyStarted(*room_id));
+        }
+        settings.validate()?;
+        check_decks(&self.decks, &settings.decks)?;
+        if settings.max_players < room.player_count() {
+            return Err(RoomError::InvalidSettings(format!(
+                "max_players cannot be below the {} players already in the room",
+                room.player_count()
+            )));
+        }
+        
+        room.settings = settings.clone();
+        self.record(*room_id, GameEvent::SettingsChanged { settings: settings.clone() });
+        self.events.push((*room_id, RoomEvent::SettingsChanged { settings }));
         Ok(())
     }
 
+    /// Move the game to its next stage (host override of the stage timers).
+    pub fn next_stage(&mut self, room_id: &RoomId) -> Result<(), RoomError> {
+        let record = |game: &GameState| GameEvent::StageAdvanced { draw: Draw::of(game) };
+        self.update_game(room_id, None, record, |game, _, _| {
+            game.next_stage();
+            Ok(())
+        })
+    }
+
+    /// Submit the current player's action, drawing the resulting image.
+    pub fn submit_action(
+        &mut self,
+        room_id: &RoomId,
+        player_id: PlayerId,
+        option_index: Option<usize>,
+    ) -> Result<(), RoomError> {
+        let record = |game: &GameState| GameEvent::ActionSubmitted {
+            player_id,
+            option_index,
+            image: game.current_image.clone(),
+            draw: Draw::of(game),
+        };
+        self.update_game(room_id, None, record, |game, _, images| {
+            game.submit_action(player_id, option_index, images)
+        })
+    }
+
+    /// Submit one player's votes for the other players.
+    pub fn submit_votes(
+        &mut self,
+        room_id: &RoomId,
+        voter_id: PlayerId,
+        votes: HashMap<PlayerId, u8>,
+    ) -> Result<(), RoomError> {
+        let recorded_votes = votes.clone();
+        let record = |game: &GameState| GameEvent::VotesSubmitted {
+            voter_id,
+            votes: recorded_votes,
+            draw: Draw::of(game),
+        };
+        self.update_game(room_id, Some(RoomEvent::VoteReceived { voter_id }), record, |game, _, _| {
+            game.submit_votes(voter_id, votes)
+        })
+    }
+
+    /// Submit an audience member's votes, scored apart from the players'.
+    pub fn submit_audience_votes(
+        &mut self,
+        room_id: &RoomId,
+        spectator_id: SpectatorId,
+        votes: HashMap<PlayerId, u8>,
+    ) -> Result<(), RoomError> {
+        let room = self.rooms.get(room_id)
+            .ok_or_else(|| RoomError::NotFound(room_id.to_string()))?;
+        room.find_spectator(spectator_id)
+            .ok_or(RoomError::SpectatorNotFound(spectator_id, *room_id))?;
+        
+        let recorded_votes = votes.clone();
+        let record = |_: &GameState| GameEvent::AudienceVotesSubmitted { spectator_id, votes: recorded_votes };
+        self.update_game(room_id, Some(RoomEvent::AudienceVoteReceived { spectator_id }), record, |game, _, _| {
+            game.submit_audience_votes(spectator_id, votes)
+        })
+    }
+
+    /// Skip the current player's turn if its deadline has passed at `now`.
+    ///
+    /// Returns the player whose turn expired, if any.
+    pub fn expire_turn(&mut self, room_id: &RoomId, now: u64) -> Result<Option<PlayerId>, RoomError> {
+        let room = self.rooms.get(room_id)
+            .ok_or_else(|| RoomError::NotFound(room_id.to_string()))?;
+        
+        let expired = room.game.as_ref()
+            .filter(|game| game.is_turn_expired(now))
+            .and_then(|game| game.current_player());
+        
+        if let Some(player_id) = expired {
+            self.submit_action(room_id, player_id, None)?;
+        }
+        
+        Ok(expired)
+    }
+
+    /// Mark a player as ready to leave the goal reveal.
+    ///
+    /// Turns begin early once every connected player is ready.
+    /// Returns true if this acknowledgement ended the reveal.
+    pub fn acknowledge_reveal(&mut self, room_id: &RoomId, player_id: PlayerId) -> Result<bool, RoomError> {
+        let record = |game: &GameState| GameEvent::RevealAcknowledged { player_id, draw: Draw::of(game) };
+        self.update_game(room_id, Some(RoomEvent::PlayerReady { player_id }), record, |game, connected, _| {
+            game.acknowledge_reveal(player_id, connected)
+        })
+    }
+
+    /// Apply any deadline-driven transition that is due at `now`.
+    ///
+    /// Ends the goal reveal or voting, or skips an expired turn.
+    pub fn advance_timers(&mut self, room_id: &RoomId, now: u64) -> Result<Option<Expiry>, RoomError> {
+        let room = self.rooms.get(room_id)
+            .ok_or_else(|| RoomError::NotFound(room_id.to_string()))?;
+        
+        let Some(game) = room.game.as_ref() else {
+            return Ok(None);
+        };
+        
+        match game.stage {
+            GameStage::RevealGoal if game.is_stage_expired(now) => {
+                self.next_stage(room_id)?;
+                Ok(Some(Expiry::RevealEnded))
+            }
+            GameStage::PlayerTurn => {
+                Ok(self.expire_turn(room_id, now)?.map(Expiry::TurnSkipped))
+            }
+            GameStage::Voting if game.is_stage_expired(now) => {
+                self.next_stage(room_id)?;
+                Ok(Some(Expiry::VotingEnded))
+            }
+            _ => Ok(None),
+        }
+    }
+
     /// Get a room by ID.
     pub fn get_room(&self, room_id: &RoomId) -> Option<&Room> {
         self.rooms.get(room_id)
@@ -173,9 +827,90 @@ impl RoomManager {
         self.code_to_id.get(code).and_then(|id| self.rooms.get(id))
     }
 
+    /// IDs of every room.
+    pub fn room_ids(&self) -> Vec<RoomId> {
+        self.rooms.keys().copied().collect()
+    }
+
+    /// Copy every room and room code for persistence.
+    pub fn snapshot(&self) -> RoomsSnapshot {
+        RoomsSnapshot {
+            code_to_id: self.code_to_id.clone(),
+            rooms: self.rooms.values().cloned().collect(),
+            logs: self.logs.clone(),
+            sessions: self.sessions.clone(),
+        }
+    }
+
+    /// Replace all rooms with previously saved ones.
+    ///
+    /// Codes that point at rooms missing from the snapshot are dropped.
+    /// Settings and the image generator are kept.
+    pub fn restore(&mut self, snapshot: RoomsSnapshot) {
+        self.rooms = snapshot.rooms.into_iter().map(|room| (room.id, room)).collect();
+        self.code_to_id = snapshot.code_to_id.into_iter()
+            .filter(|(_, id)| self.rooms.contains_key(id))
+            .collect();
+        self.logs = snapshot.logs.into_iter()
+            .filter(|(id, _)| self.rooms.contains_key(id))
+            .collect();
+        self.sessions = snapshot.sessions.into_iter()
+            .filter(|(_, session)| self.rooms.contains_key(&session.room_id))
+            .collect();
+    }
+
+    /// Run `f` against a room's game and queue events for whatever it changed.
+    ///
+    /// `f` receives the game, the currently connected players and the image
+    /// generator. If it succeeds, `announce` is queued first and `record`
+    /// builds the log entry from the updated game.
+    fn update_game<T>(
+        &mut self,
+        room_id: &RoomId,
+        announce: Option<RoomEvent>,
+        record: impl FnOnce(&GameState) -> GameEvent,
+        f: impl FnOnce(&mut GameState, &[PlayerId], &dyn ImageGenerator) -> Result<T, String>,
+    ) -> Result<T, RoomError> {
+        let room = self.rooms.get_mut(room_id)
+            .ok_or_else(|| RoomError::NotFound(room_id.to_string()))?;
+        
+        let connected: Vec<PlayerId> = room.players.iter()
+            .filter(|p| p.connected)
+            .map(|p| p.id)
+            .collect();
+        
+        let game = room.game.as_mut()
+            .ok_or_else(|| RoomError::Internal("Game not started".to_string()))?;
+        
+        let before = GameProgress::of(game);
+        let result = f(game, &connected, self.image_generator.as_ref())
+            .map_err(RoomError::Internal)?;
+        let event = record(game);
+        let changes = before.changes(game);
+        
+        self.record(*room_id, event);
+        self.events.extend(announce.into_iter().map(|event| (*room_id, event)));
+        self.events.extend(changes.into_iter().map(|event| (*room_id, event)));
+        
+        Ok(result)
+    }
+
+    /// Append an event to a room's log.
+    fn record(&mut self, room_id: RoomId, event: GameEvent) {
+        self.record_at(room_id, unix_now(), event);
+    }
+
+    /// Append an event that happened at `at` to a room's log, counting it
+    /// as activity.
+    fn record_at(&mut self, room_id: RoomId, at: u64, event: GameEvent) {
+        if let Some(room) = self.rooms.get_mut(&room_id) {
+            room.last_activity = at;
+        }
+        self.logs.entry(room_id).or_default().push(LoggedEvent { at, event });
+    }
+
     /// Generate a unique 6-character alphanumeric room code.
-    fn generate_unique_code(&self) -> String {
-        let mut rng = rand::thread_rng();
+    fn generate_unique_code(&self, rng: &mut impl Rng) -> String {
         loop {
             let code: String = (0..6)
                 .map(|_| rng.sample(Alphanumeric) as char)
@@ -189,22 +924,109 @@ impl RoomManager {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Check every deck id names one of `decks`.
+fn check_decks(decks: &[ContentPack], ids: &[String]) -> Result<(), RoomError> {
+    match ids.iter().find(|id| !decks.iter().any(|deck| &deck.id == *id)) {
+        Some(id) => Err(RoomError::InvalidSettings(format!("no deck named '{}'", id))),
+        None => Ok(()),
+    }
+}
 
-    #[test]
-    fn test_create_room() {
-        let mut manager = RoomManager::new();
-        let (id, code) = manager.create_room();
-        assert_eq!(code.len(), 6);
-        let room = manager.get_room(&id).unwrap();
-        assert_eq!(room.code.len(), 6);
-        assert_eq!(room.player_count(), 0);
+/// The selected decks combined into the content a game draws from.
+fn selected_content(decks: &[ContentPack], ids: &[String]) -> Result<ContentPack, RoomError> {
+    check_decks(decks, ids)?;
+    let selected: Vec<&ContentPack> = ids.iter()
+        .filter_map(|id| decks.iter().find(|deck| &deck.id == id))
+        .collect();
+    Ok(ContentPack::combine(&selected))
+}
+
+/// Check that a player or spectator is (still) in the room.
+fn check_member(room: &Room, participant: Participant) -> Result<(), RoomError> {
+    match participant {
+        Participant::Player { player_id } if room.find_player(player_id).is_none() => {
+            Err(RoomError::PlayerNotFound(player_id, room.id))
+        }
+        Participant::Spectator { spectator_id } if room.find_spectator(spectator_id).is_none() => {
+            Err(RoomError::SpectatorNotFound(spectator_id, room.id))
+        }
+        _ => Ok(()),
     }
+}
 
-    #[test]
-    fn test_join_room() {
+/// The parts of a game that change notifications are derived from.
+struct GameProgress {
+    stage: GameStage,
+    turn: Option<(PlayerId, u32)>,
+    turn_deadline: Option<u64>,
+    actions: usize,
+}
+
+impl GameProgress {
+    fn of(game: &GameState) -> Self {
+        Self {
+            stage: game.stage,
+            turn: Self::turn(game),
+            turn_deadline: game.turn_deadline(),
+            actions: game.actions.len(),
+        }
+    }
+
+    fn turn(game: &GameState) -> Option<(PlayerId, u32)> {
+        if game.stage != GameStage::PlayerTurn {
+            return None;
+        }
+        game.current_player().map(|id| (id, game.current_round))
+    }
+
+    /// Events describing how `game` differs from this snapshot.
+    fn changes(&self, game: &GameState) -> Vec<RoomEvent> {
+        let mut events: Vec<RoomEvent> = game.actions[self.actions.min(game.actions.len())..]
+            .iter()
+            .map(|action| RoomEvent::ActionApplied { action: action.clone() })
+            .collect();
+        
+        if game.stage != self.stage {
+            events.push(RoomEvent::StageChanged {
+                stage: game.stage,
+                deadline: game.stage_deadline(),
+            });
+        }
+        
+        // A held or restarted turn is announced again with its new deadline
+        let turn = Self::turn(game);
+        let changed = turn != self.turn || game.turn_deadline() != self.turn_deadline;
+        if let Some((player_id, round)) = turn.filter(|_| changed) {
+            events.push(RoomEvent::TurnStarted {
+                player_id,
+                round,
+                options: game.current_options.clone(),
+                deadline: game.turn_deadline(),
+            });
+        }
+        
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assets::ANIMALS;
+    use crate::settings::DisconnectPolicy;
+
+    #[test]
+    fn test_create_room() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        assert_eq!(code.len(), 6);
+        let room = manager.get_room(&id).unwrap();
+        assert_eq!(room.code.len(), 6);
+        assert_eq!(room.player_count(), 0);
+    }
+
+    #[test]
+    fn test_join_room() {
         let mut manager = RoomManager::new();
         let (id, code) = manager.create_room();
         
@@ -251,7 +1073,8 @@ mod tests {
         assert_eq!(manager.get_room(&id).unwrap().player_count(), 1);
         
         manager.leave_room(id, player_id).unwrap();
-        assert!(manager.get_room(&id).is_none(), "Room should be removed when empty");
+        let room = manager.get_room(&id).expect("Empty rooms stay open for the display");
+        assert_eq!(room.player_count(), 0);
     }
 
     #[test]
@@ -286,12 +1109,51 @@ mod tests {
         
         let (_, player_id) = manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
         
-        // Leave and rejoin
+        // Leave and join again under the same nickname
         manager.leave_room(id, player_id).ok();
-        let result = manager.join_room(&code, "Alice".to_string(), AvatarId::default());
+        let (room_id, new_id) = manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        assert_eq!(room_id, id);
+        assert_ne!(new_id, player_id);
+    }
+
+    #[test]
+    fn test_rejoin_with_resume_token() {
+        let mut manager = RoomManager::new();
+        let (id, players) = started_room(&mut manager);
+        let token = manager.issue_session(id, Participant::Player { player_id: players[1] }).unwrap();
+        manager.disconnect(id, Participant::Player { player_id: players[1] }).unwrap();
+        let code = manager.get_room(&id).unwrap().code.clone();
         
-        // After leaving, room is empty and deleted, so rejoin fails with InvalidCode
-        assert!(matches!(result, Err(RoomError::InvalidCode(_))));
+        let (room_id, player_id) = manager.rejoin_room(&code, Rejoin::ResumeToken(token)).unwrap();
+        
+        assert_eq!((room_id, player_id), (id, players[1]));
+        let room = manager.get_room(&id).unwrap();
+        assert!(room.find_player(players[1]).unwrap().connected);
+        // Same seat in turn order
+        assert_eq!(room.game.as_ref().unwrap().players_in_order, players);
+    }
+
+    #[test]
+    fn test_rejoin_rejects_bad_credentials() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        let (_, alice) = manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        let display_token = manager.issue_session(id, Participant::Display).unwrap();
+        
+        let result = manager.rejoin_room(&code, Rejoin::ResumeToken(SessionToken::generate()));
+        assert!(matches!(result, Err(RoomError::Unauthorized)));
+        let result = manager.rejoin_room(&code, Rejoin::ResumeToken(display_token));
+        assert!(matches!(result, Err(RoomError::Forbidden(_))));
+        let result = manager.rejoin_room(&code, Rejoin::Nickname("Bob".to_string()));
+        assert!(matches!(result, Err(RoomError::NicknameNotFound(_, _))));
+        
+        // Nobody can take over a connected player's seat by nickname
+        let result = manager.rejoin_room(&code, Rejoin::Nickname("Alice".to_string()));
+        assert!(matches!(result, Err(RoomError::Forbidden(_))));
+        
+        manager.disconnect(id, Participant::Player { player_id: alice }).unwrap();
+        let (_, player_id) = manager.rejoin_room(&code, Rejoin::Nickname("Alice".to_string())).unwrap();
+        assert_eq!(player_id, alice);
     }
 
     #[test]
@@ -309,9 +1171,10 @@ mod tests {
         manager.leave_room(id, p2).unwrap();
         assert_eq!(manager.get_room(&id).unwrap().player_count(), 2);
         
-        // Remove first player (host) - room should be deleted
+        // The first player is not the host; the room stays open
         manager.leave_room(id, p1).unwrap();
-        assert!(manager.get_room(&id).is_none());
+        let room = manager.get_room(&id).unwrap();
+        assert_eq!(room.players.iter().map(|p| p.id).collect::<Vec<_>>(), vec![p3]);
     }
 
     #[test]
@@ -376,4 +1239,986 @@ mod tests {
         
         assert!(manager.get_room_mut(&id).is_some());
     }
+
+    #[derive(Debug)]
+    struct FailingImageGenerator;
+
+    impl ImageGenerator for FailingImageGenerator {
+        fn generate(&self, _request: &ImageRequest) -> Result<crate::types::ImageId, crate::errors::ImageError> {
+            Err(crate::errors::ImageError::Unavailable("offline".to_string()))
+        }
+    }
+
+    fn started_room(manager: &mut RoomManager) -> (RoomId, Vec<PlayerId>) {
+        let (id, code) = manager.create_room();
+        let (_, p1) = manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        let (_, p2) = manager.join_room(&code, "Bob".to_string(), AvatarId::default()).unwrap();
+        manager.start_game(&id).unwrap();
+        manager.get_room_mut(&id).unwrap().game.as_mut().unwrap().next_stage();
+        (id, vec![p1, p2])
+    }
+
+    #[test]
+    fn test_same_seed_draws_the_same_game() {
+        let draw = |seed: u64| {
+            let mut manager = RoomManager::new();
+            let (id, code) = manager.create_seeded_room(seed);
+            let mut players = Vec::new();
+            for name in ["Alice", "Bob", "Carol"] {
+                players.push(manager.join_room(&code, name.to_string(), AvatarId::default()).unwrap().1);
+            }
+            manager.start_game(&id).unwrap();
+            manager.next_stage(&id).unwrap();
+
+            let game = manager.get_room(&id).unwrap().game.as_ref().unwrap();
+            let objects: Vec<String> = players.iter().map(|p| game.player_starting_objects[p].clone()).collect();
+            (code, game.communal_goal.clone(), objects, game.current_options.clone(), game.rng.seed())
+        };
+
+        let first = draw(42);
+        assert_eq!(first, draw(42));
+        assert_eq!(first.4, 42);
+        assert_ne!(first, draw(43));
+    }
+
+    #[test]
+    fn test_start_game_generates_images() {
+        let mut manager = RoomManager::new();
+        let (id, _) = started_room(&mut manager);
+        
+        let game = manager.get_room(&id).unwrap().game.as_ref().unwrap();
+        assert!(game.goal_image.as_str().starts_with("goal_"));
+        assert!(game.starting_image.as_str().starts_with("start_"));
+        assert_eq!(game.current_image, game.starting_image);
+    }
+
+    #[test]
+    fn test_closing_a_room_deletes_images_only_it_shows() {
+        let dir = std::env::temp_dir().join(format!("big-picture-images-{}", RoomId::new()));
+        let generator = crate::procedural_images::ProceduralImageGenerator::new(&dir);
+        let mut manager = RoomManager::with_image_generator(Arc::new(generator));
+        let files = || std::fs::read_dir(&dir).map_or(0, |entries| entries.count());
+
+        // Same seed, same goal and objects: both rooms show the same files
+        let mut rooms = Vec::new();
+        for _ in 0..2 {
+            let (id, code) = manager.create_seeded_room(7);
+            for name in ["Alice", "Bob"] {
+                manager.join_room(&code, name.to_string(), AvatarId::default()).unwrap();
+            }
+            manager.start_game(&id).unwrap();
+            rooms.push(id);
+        }
+        let shown = files();
+        assert!(shown > 0);
+
+        manager.close_room(rooms[0]).unwrap();
+        assert_eq!(files(), shown);
+        manager.close_room(rooms[1]).unwrap();
+        assert_eq!(files(), 0);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    fn tiny_deck() -> ContentPack {
+        ContentPack {
+            id: "tiny".to_string(),
+            name: "Tiny".to_string(),
+            animals: vec!["A bat".to_string()],
+            objects: ["A pumpkin", "A broom", "A candle", "A cauldron", "A skull", "A lantern", "A coffin"]
+                .iter().map(|s| s.to_string()).collect(),
+            locations: vec!["in a crypt".to_string()],
+            ..ContentPack::builtin()
+        }
+    }
+
+    #[test]
+    fn test_games_draw_from_the_selected_decks() {
+        let deck = tiny_deck();
+        let mut manager = RoomManager::new();
+        manager.set_decks(vec![deck.clone()]).unwrap();
+        assert_eq!(manager.decks().len(), 2);
+        manager.set_default_settings(GameSettings { decks: vec!["tiny".to_string()], ..GameSettings::default() }).unwrap();
+        let (id, _) = started_room(&mut manager);
+
+        let game = manager.get_room(&id).unwrap().game.as_ref().unwrap();
+        assert!(game.communal_goal.starts_with("A bat holding"));
+        assert!(game.communal_goal.ends_with("in a crypt"));
+        for object in game.player_starting_objects.values() {
+            assert!(deck.animals.contains(object) || deck.objects.contains(object));
+        }
+        assert_eq!(game.content, deck);
+    }
+
+    #[test]
+    fn test_host_picks_decks_in_lobby() {
+        let mut manager = RoomManager::new();
+        manager.set_decks(vec![tiny_deck()]).unwrap();
+        let (id, code) = manager.create_room();
+        manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        manager.join_room(&code, "Bob".to_string(), AvatarId::default()).unwrap();
+
+        let unknown = GameSettings { decks: vec!["spooky".to_string()], ..GameSettings::default() };
+        let err = manager.update_settings(&id, unknown).unwrap_err();
+        assert!(err.to_string().contains("no deck named 'spooky'"), "{}", err);
+
+        let both = vec!["tiny".to_string(), "default".to_string()];
+        manager.update_settings(&id, GameSettings { decks: both, ..GameSettings::default() }).unwrap();
+        manager.start_game(&id).unwrap();
+
+        let game = manager.get_room(&id).unwrap().game.as_ref().unwrap();
+        assert_eq!(game.content.id, "tiny+default");
+        assert!(game.content.animals.contains(&"A bat".to_string()));
+        assert!(game.content.animals.contains(&ANIMALS[0].to_string()));
+    }
+
+    #[test]
+    fn test_set_decks_rejects_invalid_and_duplicate_decks() {
+        let mut manager = RoomManager::new();
+        assert!(manager.set_decks(vec![ContentPack { modifiers: Default::default(), ..tiny_deck() }]).is_err());
+        let err = manager.set_decks(vec![tiny_deck(), tiny_deck()]).unwrap_err();
+        assert!(matches!(err, ContentError::DuplicatePack(_)));
+        assert_eq!(manager.decks().len(), 1);
+
+        // A deck with the built-in id replaces it
+        let default = ContentPack { id: "default".to_string(), ..tiny_deck() };
+        manager.set_decks(vec![default.clone()]).unwrap();
+        assert_eq!(manager.decks(), [default]);
+    }
+
+    #[test]
+    fn test_submit_action_records_new_image() {
+        let mut manager = RoomManager::new();
+        let (id, players) = started_room(&mut manager);
+        let before = manager.get_room(&id).unwrap().game.as_ref().unwrap().current_image.clone();
+        
+        manager.submit_action(&id, players[0], Some(0)).unwrap();
+        
+        let game = manager.get_room(&id).unwrap().game.as_ref().unwrap();
+        assert_ne!(game.current_image, before);
+        assert_eq!(game.actions[0].resulting_image, game.current_image);
+    }
+
+    #[test]
+    fn test_skip_keeps_current_image() {
+        let mut manager = RoomManager::new();
+        let (id, players) = started_room(&mut manager);
+        let before = manager.get_room(&id).unwrap().game.as_ref().unwrap().current_image.clone();
+        
+        manager.submit_action(&id, players[0], None).unwrap();
+        
+        let game = manager.get_room(&id).unwrap().game.as_ref().unwrap();
+        assert_eq!(game.current_image, before);
+        assert_eq!(game.actions[0].resulting_image, before);
+    }
+
+    #[test]
+    fn test_start_game_fails_when_images_fail() {
+        let mut manager = RoomManager::with_image_generator(Arc::new(FailingImageGenerator));
+        let (id, code) = manager.create_room();
+        manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        manager.join_room(&code, "Bob".to_string(), AvatarId::default()).unwrap();
+        
+        let result = manager.start_game(&id);
+        assert!(matches!(result, Err(RoomError::ImageGeneration(_))));
+        assert_eq!(manager.get_room(&id).unwrap().state, RoomState::Lobby);
+    }
+
+    #[test]
+    fn test_submit_action_without_game() {
+        let mut manager = RoomManager::new();
+        let (id, _code) = manager.create_room();
+        let result = manager.submit_action(&id, PlayerId::new(), None);
+        assert!(matches!(result, Err(RoomError::Internal(_))));
+    }
+
+    #[test]
+    fn test_expire_turn_skips_after_deadline() {
+        let mut manager = RoomManager::new();
+        manager.set_default_settings(GameSettings { turn_seconds: 5, ..GameSettings::default() }).unwrap();
+        let (id, players) = started_room(&mut manager);
+        let start = manager.get_room(&id).unwrap().game.as_ref().unwrap().turn_start_time.unwrap();
+        
+        assert_eq!(manager.expire_turn(&id, start + 4).unwrap(), None);
+        assert_eq!(manager.expire_turn(&id, start + 5).unwrap(), Some(players[0]));
+        
+        let game = manager.get_room(&id).unwrap().game.as_ref().unwrap();
+        assert_eq!(game.actions.len(), 1);
+        assert_eq!(game.actions[0].option_chosen, None);
+        assert_eq!(game.current_player(), Some(players[1]));
+    }
+
+    #[test]
+    fn test_expire_turn_outside_turn_stage() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        manager.join_room(&code, "Bob".to_string(), AvatarId::default()).unwrap();
+        manager.start_game(&id).unwrap();
+        
+        // Still revealing the goal: nothing to expire
+        assert_eq!(manager.expire_turn(&id, u64::MAX).unwrap(), None);
+    }
+
+    fn revealing_room(manager: &mut RoomManager) -> (RoomId, Vec<PlayerId>) {
+        let (id, code) = manager.create_room();
+        let (_, p1) = manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        let (_, p2) = manager.join_room(&code, "Bob".to_string(), AvatarId::default()).unwrap();
+        manager.start_game(&id).unwrap();
+        (id, vec![p1, p2])
+    }
+
+    #[test]
+    fn test_reveal_ends_after_deadline() {
+        let mut manager = RoomManager::new();
+        manager.set_default_settings(GameSettings { reveal_seconds: 8, ..GameSettings::default() }).unwrap();
+        let (id, _) = revealing_room(&mut manager);
+        let start = manager.get_room(&id).unwrap().game.as_ref().unwrap().stage_start_time;
+        
+        assert_eq!(manager.advance_timers(&id, start + 7).unwrap(), None);
+        assert_eq!(manager.advance_timers(&id, start + 8).unwrap(), Some(Expiry::RevealEnded));
+        
+        let game = manager.get_room(&id).unwrap().game.as_ref().unwrap();
+        assert_eq!(game.stage, GameStage::PlayerTurn);
+    }
+
+    #[test]
+    fn test_advance_timers_skips_expired_turn() {
+        let mut manager = RoomManager::new();
+        let (id, players) = revealing_room(&mut manager);
+        manager.get_room_mut(&id).unwrap().game.as_mut().unwrap().next_stage();
+        let deadline = manager.get_room(&id).unwrap().game.as_ref().unwrap().turn_deadline().unwrap();
+        
+        assert_eq!(manager.advance_timers(&id, deadline).unwrap(), Some(Expiry::TurnSkipped(players[0])));
+    }
+
+    #[test]
+    fn test_disconnected_player_turn_is_skipped() {
+        let mut manager = RoomManager::new();
+        let (id, players) = started_room(&mut manager);
+        manager.take_events();
+        
+        manager.disconnect(id, Participant::Player { player_id: players[0] }).unwrap();
+        
+        let game = manager.get_room(&id).unwrap().game.as_ref().unwrap();
+        assert_eq!(game.current_player(), Some(players[1]));
+        assert_eq!(game.actions[0].modification, "No action");
+        let events = manager.take_events();
+        assert!(matches!(events[1].1, RoomEvent::ActionApplied { .. }));
+        assert!(matches!(events[2].1, RoomEvent::TurnStarted { player_id, .. } if player_id == players[1]));
+        
+        // Leaving mid-game counts as gone for good
+        manager.reconnect(id, Participant::Player { player_id: players[0] }).unwrap();
+        manager.submit_action(&id, players[1], None).unwrap();
+        manager.leave_room(id, players[0]).unwrap();
+        let game = manager.get_room(&id).unwrap().game.as_ref().unwrap();
+        assert_eq!(game.current_player(), Some(players[1]));
+    }
+
+    #[test]
+    fn test_wait_policy_announces_held_turn() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        let settings = GameSettings { disconnect_policy: DisconnectPolicy::Wait, ..GameSettings::default() };
+        manager.update_settings(&id, settings).unwrap();
+        let (_, alice) = manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        manager.join_room(&code, "Bob".to_string(), AvatarId::default()).unwrap();
+        manager.start_game(&id).unwrap();
+        manager.next_stage(&id).unwrap();
+        manager.take_events();
+        
+        manager.disconnect(id, Participant::Player { player_id: alice }).unwrap();
+        let events = manager.take_events();
+        let held = manager.get_room(&id).unwrap().game.as_ref().unwrap().turn_start_time.unwrap();
+        let wait_deadline = held + crate::settings::MAX_WAIT_SECONDS;
+        assert!(matches!(
+            events.last(),
+            Some((_, RoomEvent::TurnStarted { player_id, deadline: Some(d), .. })) if *player_id == alice && *d == wait_deadline
+        ));
+        
+        manager.reconnect(id, Participant::Player { player_id: alice }).unwrap();
+        let events = manager.take_events();
+        assert!(matches!(
+            events.last(),
+            Some((_, RoomEvent::TurnStarted { player_id, deadline: Some(_), .. })) if *player_id == alice
+        ));
+    }
+
+    #[test]
+    fn test_wait_policy_gives_up_on_a_player_who_does_not_return() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        let settings = GameSettings { disconnect_policy: DisconnectPolicy::Wait, ..GameSettings::default() };
+        manager.update_settings(&id, settings).unwrap();
+        let (_, alice) = manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        let (_, bob) = manager.join_room(&code, "Bob".to_string(), AvatarId::default()).unwrap();
+        manager.start_game(&id).unwrap();
+        manager.next_stage(&id).unwrap();
+        
+        manager.disconnect(id, Participant::Player { player_id: alice }).unwrap();
+        let deadline = manager.get_room(&id).unwrap().game.as_ref().unwrap().turn_deadline().unwrap();
+        assert_eq!(manager.advance_timers(&id, deadline - 1).unwrap(), None);
+        assert_eq!(manager.advance_timers(&id, deadline).unwrap(), Some(Expiry::TurnSkipped(alice)));
+        let game = manager.get_room(&id).unwrap().game.as_ref().unwrap();
+        assert_eq!(game.current_player(), Some(bob));
+    }
+
+    #[test]
+    fn test_current_player_leaving_under_wait_keeps_the_game_going() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        let settings = GameSettings { disconnect_policy: DisconnectPolicy::Wait, ..GameSettings::default() };
+        manager.update_settings(&id, settings).unwrap();
+        let mut players = Vec::new();
+        for name in ["Alice", "Bob", "Carol"] {
+            players.push(manager.join_room(&code, name.to_string(), AvatarId::default()).unwrap().1);
+        }
+        manager.start_game(&id).unwrap();
+        manager.next_stage(&id).unwrap();
+        
+        manager.leave_room(id, players[0]).unwrap();
+        let game = manager.get_room(&id).unwrap().game.as_ref().unwrap();
+        assert_eq!(game.current_player(), Some(players[1]));
+        assert_eq!(game.actions[0].modification, "No action");
+        
+        // Next round goes straight past them
+        manager.submit_action(&id, players[1], Some(0)).unwrap();
+        manager.submit_action(&id, players[2], None).unwrap();
+        let game = manager.get_room(&id).unwrap().game.as_ref().unwrap();
+        assert_eq!((game.current_round, game.current_player()), (1, Some(players[1])));
+        
+        let room = manager.get_room(&id).unwrap();
+        let replayed = crate::game_log::replay(manager.game_log(&id).unwrap()).unwrap();
+        assert_eq!(serde_json::to_value(&replayed).unwrap(), serde_json::to_value(room).unwrap());
+    }
+
+    #[test]
+    fn test_reveal_ends_when_connected_players_acknowledge() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        let (_, p1) = manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        let (_, p2) = manager.join_room(&code, "Bob".to_string(), AvatarId::default()).unwrap();
+        let (_, p3) = manager.join_room(&code, "Carol".to_string(), AvatarId::default()).unwrap();
+        manager.start_game(&id).unwrap();
+        manager.get_room_mut(&id).unwrap().find_player_mut(p3).unwrap().disconnect();
+        
+        assert!(!manager.acknowledge_reveal(&id, p1).unwrap());
+        assert!(manager.acknowledge_reveal(&id, p2).unwrap());
+        
+        let game = manager.get_room(&id).unwrap().game.as_ref().unwrap();
+        assert_eq!(game.stage, GameStage::PlayerTurn);
+    }
+
+    #[test]
+    fn test_lobby_events() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        let (_, alice) = manager.join_room(&code, "Alice".to_string(), AvatarId::new(2)).unwrap();
+        let (_, bob) = manager.join_room(&code, "Bob".to_string(), AvatarId::default()).unwrap();
+        manager.leave_room(id, bob).unwrap();
+        
+        let events = manager.take_events();
+        assert_eq!(events, vec![
+            (id, RoomEvent::PlayerJoined { player_id: alice, nickname: "Alice".to_string(), avatar_id: AvatarId::new(2) }),
+            (id, RoomEvent::PlayerJoined { player_id: bob, nickname: "Bob".to_string(), avatar_id: AvatarId::default() }),
+            (id, RoomEvent::PlayerLeft { player_id: bob }),
+        ]);
+        assert!(manager.take_events().is_empty(), "Events are drained");
+    }
+
+    #[test]
+    fn test_room_closed_event() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        manager.take_events();
+        
+        manager.close_room(id).unwrap();
+        
+        assert_eq!(manager.take_events(), vec![(id, RoomEvent::RoomClosed)]);
+        assert!(manager.get_room(&id).is_none());
+        assert!(manager.get_room_by_code(&code).is_none());
+        assert!(manager.game_log(&id).is_none());
+        assert!(matches!(manager.close_room(id), Err(RoomError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_only_host_is_authorized() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        let (_, alice) = manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        let display_token = manager.issue_session(id, Participant::Display).unwrap();
+        let alice_token = manager.issue_session(id, Participant::Player { player_id: alice }).unwrap();
+        
+        assert_eq!(manager.get_room(&id).unwrap().host, Participant::Display);
+        assert!(manager.authorize_host(&id, &display_token).is_ok());
+        assert!(matches!(manager.authorize_host(&id, &alice_token), Err(RoomError::Forbidden(_))));
+    }
+
+    #[test]
+    fn test_host_migrates_when_display_disconnects() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        let (_, alice) = manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        let (_, bob) = manager.join_room(&code, "Bob".to_string(), AvatarId::default()).unwrap();
+        manager.get_room_mut(&id).unwrap().find_player_mut(alice).unwrap().connected_since = 200;
+        manager.get_room_mut(&id).unwrap().find_player_mut(bob).unwrap().connected_since = 100;
+        let bob_token = manager.issue_session(id, Participant::Player { player_id: bob }).unwrap();
+        manager.take_events();
+        
+        manager.disconnect(id, Participant::Display).unwrap();
+        
+        let bob_host = Participant::Player { player_id: bob };
+        assert_eq!(manager.get_room(&id).unwrap().host, bob_host);
+        assert!(manager.authorize_host(&id, &bob_token).is_ok());
+        assert_eq!(manager.take_events(), vec![
+            (id, RoomEvent::ConnectionChanged { participant: Participant::Display, connected: false }),
+            (id, RoomEvent::HostChanged { host: bob_host }),
+        ]);
+        
+        // The host leaving mid-game hands the role on again
+        manager.leave_room(id, bob).unwrap();
+        assert_eq!(manager.get_room(&id).unwrap().host, Participant::Player { player_id: alice });
+    }
+
+    #[test]
+    fn test_reconnecting_display_reclaims_host() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        let (_, alice) = manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        manager.disconnect(id, Participant::Display).unwrap();
+        assert_eq!(manager.get_room(&id).unwrap().host, Participant::Player { player_id: alice });
+        manager.take_events();
+        
+        manager.reconnect(id, Participant::Display).unwrap();
+        
+        assert_eq!(manager.get_room(&id).unwrap().host, Participant::Display);
+        assert_eq!(manager.take_events(), vec![
+            (id, RoomEvent::ConnectionChanged { participant: Participant::Display, connected: true }),
+            (id, RoomEvent::HostChanged { host: Participant::Display }),
+        ]);
+    }
+
+    #[test]
+    fn test_room_closes_when_nobody_is_left() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        let (_, alice) = manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        manager.disconnect(id, Participant::Display).unwrap();
+        manager.disconnect(id, Participant::Player { player_id: alice }).unwrap();
+        assert!(manager.get_room(&id).is_some());
+        manager.take_events();
+        
+        manager.leave_room(id, alice).unwrap();
+        
+        assert!(manager.get_room(&id).is_none());
+        assert_eq!(manager.take_events(), vec![
+            (id, RoomEvent::PlayerLeft { player_id: alice }),
+            (id, RoomEvent::RoomClosed),
+        ]);
+    }
+
+    #[test]
+    fn test_disconnect_unknown_player() {
+        let mut manager = RoomManager::new();
+        let (id, _) = manager.create_room();
+        let stranger = PlayerId::new();
+        assert!(matches!(
+            manager.disconnect(id, Participant::Player { player_id: stranger }),
+            Err(RoomError::PlayerNotFound(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_sweep_disconnects_silent_participants() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        let (_, alice) = manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        let (_, bob) = manager.join_room(&code, "Bob".to_string(), AvatarId::default()).unwrap();
+        let now = unix_now();
+        manager.touch(id, Participant::Display, now + 10).unwrap();
+        manager.touch(id, Participant::Player { player_id: alice }, now + 10).unwrap();
+        manager.take_events();
+        
+        // Within the grace period nobody is dropped
+        assert!(manager.sweep_presence(now + 15, 15).is_empty());
+        
+        let bob_participant = Participant::Player { player_id: bob };
+        assert_eq!(manager.sweep_presence(now + 20, 15), vec![(id, bob_participant)]);
+        let room = manager.get_room(&id).unwrap();
+        assert!(room.find_player(alice).unwrap().connected);
+        assert!(!room.find_player(bob).unwrap().connected);
+        assert_eq!(manager.take_events(), vec![
+            (id, RoomEvent::ConnectionChanged { participant: bob_participant, connected: false }),
+        ]);
+        
+        // Hearing from Bob again reconnects him
+        manager.touch(id, bob_participant, now + 21).unwrap();
+        assert!(manager.get_room(&id).unwrap().find_player(bob).unwrap().connected);
+        assert_eq!(manager.take_events(), vec![
+            (id, RoomEvent::ConnectionChanged { participant: bob_participant, connected: true }),
+        ]);
+    }
+
+    #[test]
+    fn test_sweep_hands_on_host_of_silent_display() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        let (_, alice) = manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        let now = unix_now();
+        manager.touch(id, Participant::Player { player_id: alice }, now + 30).unwrap();
+        
+        assert_eq!(manager.sweep_presence(now + 30, 15), vec![(id, Participant::Display)]);
+        assert_eq!(manager.get_room(&id).unwrap().host, Participant::Player { player_id: alice });
+        
+        manager.touch(id, Participant::Display, now + 31).unwrap();
+        assert_eq!(manager.get_room(&id).unwrap().host, Participant::Display);
+    }
+
+    #[test]
+    fn test_restored_participants_get_grace_period() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        
+        let mut restored = RoomManager::new();
+        restored.restore(manager.snapshot());
+        let later = unix_now() + 1000;
+        assert!(restored.sweep_presence(later, 15).is_empty());
+        assert_eq!(restored.sweep_presence(later + 16, 15).len(), 2);
+        assert!(restored.get_room(&id).is_some());
+    }
+
+    #[test]
+    fn test_touch_unknown_player() {
+        let mut manager = RoomManager::new();
+        let (id, _) = manager.create_room();
+        let stranger = Participant::Player { player_id: PlayerId::new() };
+        assert!(matches!(manager.touch(id, stranger, 0), Err(RoomError::PlayerNotFound(_, _))));
+    }
+
+    #[test]
+    fn test_display_takes_no_seat() {
+        let mut manager = RoomManager::new();
+        let (id, players) = started_room(&mut manager);
+        let room = manager.get_room(&id).unwrap();
+        assert_eq!(room.player_count(), players.len());
+        assert_eq!(room.game.as_ref().unwrap().players_in_order, players);
+    }
+
+    #[test]
+    fn test_turn_events() {
+        let mut manager = RoomManager::new();
+        let (id, players) = revealing_room(&mut manager);
+        manager.take_events();
+        
+        manager.next_stage(&id).unwrap();
+        let events: Vec<RoomEvent> = manager.take_events().into_iter().map(|(_, e)| e).collect();
+        assert!(matches!(events[0], RoomEvent::StageChanged { stage: GameStage::PlayerTurn, .. }));
+        assert!(matches!(&events[1], RoomEvent::TurnStarted { player_id, round: 0, options, .. }
+            if *player_id == players[0] && !options.is_empty()));
+        
+        manager.submit_action(&id, players[0], Some(1)).unwrap();
+        let events: Vec<RoomEvent> = manager.take_events().into_iter().map(|(_, e)| e).collect();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(&events[0], RoomEvent::ActionApplied { action } if action.player_id == players[0]));
+        assert!(matches!(&events[1], RoomEvent::TurnStarted { player_id, .. } if *player_id == players[1]));
+    }
+
+    #[test]
+    fn test_failed_mutation_emits_nothing() {
+        let mut manager = RoomManager::new();
+        let (id, players) = revealing_room(&mut manager);
+        manager.take_events();
+        
+        // Not in the turn stage yet
+        assert!(manager.submit_action(&id, players[0], Some(0)).is_err());
+        assert!(manager.take_events().is_empty());
+    }
+
+    #[test]
+    fn test_vote_events() {
+        let mut manager = RoomManager::new();
+        let (id, players) = revealing_room(&mut manager);
+        manager.next_stage(&id).unwrap();
+        manager.next_stage(&id).unwrap();
+        manager.take_events();
+        
+        let votes = HashMap::from([(players[1], 4)]);
+        manager.submit_votes(&id, players[0], votes).unwrap();
+        let votes = HashMap::from([(players[0], 5)]);
+        manager.submit_votes(&id, players[1], votes).unwrap();
+        
+        let events: Vec<RoomEvent> = manager.take_events().into_iter().map(|(_, e)| e).collect();
+        assert_eq!(events, vec![
+            RoomEvent::VoteReceived { voter_id: players[0] },
+            RoomEvent::VoteReceived { voter_id: players[1] },
+            RoomEvent::StageChanged { stage: GameStage::Results, deadline: None },
+        ]);
+    }
+
+    #[test]
+    fn test_absent_voter_does_not_block_results() {
+        let mut manager = RoomManager::new();
+        let (id, players) = revealing_room(&mut manager);
+        manager.next_stage(&id).unwrap();
+        manager.next_stage(&id).unwrap();
+        manager.submit_votes(&id, players[0], HashMap::from([(players[1], 4)])).unwrap();
+        manager.take_events();
+        
+        manager.disconnect(id, Participant::Player { player_id: players[1] }).unwrap();
+        
+        let events: Vec<RoomEvent> = manager.take_events().into_iter().map(|(_, e)| e).collect();
+        assert!(events.contains(&RoomEvent::StageChanged { stage: GameStage::Results, deadline: None }));
+        let game = manager.get_room(&id).unwrap().game.as_ref().unwrap();
+        assert_eq!(game.calculate_scores().players[&players[1]], 4.0);
+    }
+
+    #[test]
+    fn test_snapshot_restore_round_trip() {
+        let mut manager = RoomManager::new();
+        let (id, players) = started_room(&mut manager);
+        manager.submit_action(&id, players[0], Some(0)).unwrap();
+        let (lobby_id, lobby_code) = manager.create_room();
+        
+        let json = serde_json::to_string(&manager.snapshot()).expect("Should serialize");
+        let snapshot: RoomsSnapshot = serde_json::from_str(&json).expect("Should deserialize");
+        
+        let mut restored = RoomManager::new();
+        restored.restore(snapshot);
+        
+        let room = restored.get_room(&id).unwrap();
+        let original = manager.get_room(&id).unwrap();
+        assert_eq!(room.state, RoomState::InGame);
+        let game = room.game.as_ref().unwrap();
+        assert_eq!(game.actions.len(), 1);
+        assert_eq!(game.current_player(), original.game.as_ref().unwrap().current_player());
+        assert_eq!(restored.get_room_by_code(&lobby_code).map(|r| r.id), Some(lobby_id));
+        
+        // Restored games keep going
+        restored.submit_action(&id, players[1], Some(0)).unwrap();
+    }
+
+    #[test]
+    fn test_sessions_authenticate_room_members() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        let (other_id, _) = manager.create_room();
+        let (_, alice) = manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        
+        let display_token = manager.issue_session(id, Participant::Display).unwrap();
+        let alice_token = manager.issue_session(id, Participant::Player { player_id: alice }).unwrap();
+        assert_ne!(display_token, alice_token);
+        
+        assert_eq!(manager.authenticate_player(&id, &alice_token).unwrap(), alice);
+        assert_eq!(manager.authenticate(&id, &display_token).unwrap().participant, Participant::Display);
+        assert!(matches!(manager.authenticate_player(&id, &display_token), Err(RoomError::Forbidden(_))));
+        assert!(matches!(manager.authenticate(&other_id, &alice_token), Err(RoomError::Unauthorized)));
+        assert!(matches!(
+            manager.authenticate(&id, &SessionToken::new(alice.to_string())),
+            Err(RoomError::Unauthorized)
+        ));
+        let stranger = Participant::Player { player_id: PlayerId::new() };
+        assert!(manager.issue_session(id, stranger).is_err());
+    }
+
+    #[test]
+    fn test_sessions_revoked_on_reissue_and_leave() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        let (_, alice) = manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        let (_, bob) = manager.join_room(&code, "Bob".to_string(), AvatarId::default()).unwrap();
+        let bob_participant = Participant::Player { player_id: bob };
+        
+        let old_token = manager.issue_session(id, bob_participant).unwrap();
+        let new_token = manager.issue_session(id, bob_participant).unwrap();
+        assert!(manager.authenticate(&id, &old_token).is_err());
+        assert!(manager.authenticate(&id, &new_token).is_ok());
+        
+        manager.leave_room(id, bob).unwrap();
+        assert!(manager.authenticate(&id, &new_token).is_err());
+        
+        // Closing the room signs everyone out
+        let display_token = manager.issue_session(id, Participant::Display).unwrap();
+        let alice_token = manager.issue_session(id, Participant::Player { player_id: alice }).unwrap();
+        manager.close_room(id).unwrap();
+        assert!(manager.authenticate(&id, &display_token).is_err());
+        assert!(manager.authenticate(&id, &alice_token).is_err());
+    }
+
+    #[test]
+    fn test_sessions_survive_restore() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        let (_, alice) = manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        let token = manager.issue_session(id, Participant::Player { player_id: alice }).unwrap();
+        
+        let json = serde_json::to_string(&manager.snapshot()).expect("Should serialize");
+        let mut restored = RoomManager::new();
+        restored.restore(serde_json::from_str(&json).expect("Should deserialize"));
+        assert_eq!(restored.authenticate_player(&id, &token).unwrap(), alice);
+    }
+
+    #[test]
+    fn test_restore_drops_dangling_codes() {
+        let mut snapshot = RoomsSnapshot::default();
+        snapshot.code_to_id.insert("GHOST1".to_string(), RoomId::new());
+        
+        let mut manager = RoomManager::new();
+        manager.restore(snapshot);
+        assert!(manager.get_room_by_code("GHOST1").is_none());
+        assert!(manager.room_ids().is_empty());
+    }
+
+    #[test]
+    fn test_update_settings_in_lobby() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        let settings = GameSettings { rounds: 1, min_players: 3, max_players: 3, ..GameSettings::default() };
+        manager.update_settings(&id, settings.clone()).unwrap();
+        
+        for name in ["Alice", "Bob"] {
+            manager.join_room(&code, name.to_string(), AvatarId::default()).unwrap();
+        }
+        let err = manager.start_game(&id).unwrap_err();
+        assert!(matches!(err, RoomError::NotEnoughPlayers(_, 3)));
+        assert!(err.to_string().contains("need at least 3"), "{}", err);
+        
+        manager.join_room(&code, "Carol".to_string(), AvatarId::default()).unwrap();
+        let result = manager.join_room(&code, "Dave".to_string(), AvatarId::default());
+        assert!(matches!(result, Err(RoomError::Full(_))));
+        
+        manager.start_game(&id).unwrap();
+        let game = manager.get_room(&id).unwrap().game.as_ref().unwrap();
+        assert_eq!(game.max_rounds, 1);
+        assert_eq!(game.settings, settings);
+    }
+
+    #[test]
+    fn test_update_settings_validation() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        
+        let invalid = GameSettings { turn_seconds: 0, ..GameSettings::default() };
+        assert!(matches!(manager.update_settings(&id, invalid), Err(RoomError::InvalidSettings(_))));
+        
+        for name in ["Alice", "Bob", "Carol"] {
+            manager.join_room(&code, name.to_string(), AvatarId::default()).unwrap();
+        }
+        let too_small = GameSettings { min_players: 2, max_players: 2, ..GameSettings::default() };
+        assert!(matches!(manager.update_settings(&id, too_small), Err(RoomError::InvalidSettings(_))));
+        
+        manager.start_game(&id).unwrap();
+        let result = manager.update_settings(&id, GameSettings::default());
+        assert!(matches!(result, Err(RoomError::AlreadyStarted(_))));
+    }
+
+    #[test]
+    fn test_voting_ends_after_deadline() {
+        let mut manager = RoomManager::new();
+        let (id, _) = revealing_room(&mut manager);
+        manager.next_stage(&id).unwrap();
+        manager.next_stage(&id).unwrap();
+        let deadline = manager.get_room(&id).unwrap().game.as_ref().unwrap().stage_deadline().unwrap();
+        
+        assert_eq!(manager.advance_timers(&id, deadline - 1).unwrap(), None);
+        assert_eq!(manager.advance_timers(&id, deadline).unwrap(), Some(Expiry::VotingEnded));
+        assert_eq!(manager.get_room(&id).unwrap().game.as_ref().unwrap().stage, GameStage::Results);
+    }
+
+    #[test]
+    fn test_spectators_join_at_any_state() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        manager.update_settings(&id, GameSettings { max_players: 2, ..GameSettings::default() }).unwrap();
+        manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        manager.join_room(&code, "Bob".to_string(), AvatarId::default()).unwrap();
+        manager.take_events();
+        
+        // A full lobby still takes spectators, and so does a game in progress
+        let (_, dana) = manager.join_as_spectator(&code, "Dana".to_string()).unwrap();
+        manager.start_game(&id).unwrap();
+        let (_, eve) = manager.join_as_spectator(&code, "Eve".to_string()).unwrap();
+        assert!(matches!(
+            manager.join_as_spectator(&code, "Eve".to_string()),
+            Err(RoomError::NicknameTaken(_, _))
+        ));
+        
+        let room = manager.get_room(&id).unwrap();
+        assert_eq!(room.player_count(), 2);
+        assert_eq!(room.spectators.len(), 2);
+        assert_eq!(room.game.as_ref().unwrap().players_in_order.len(), 2);
+        
+        let events: Vec<RoomEvent> = manager.take_events().into_iter().map(|(_, e)| e).collect();
+        assert_eq!(events[0], RoomEvent::SpectatorJoined { spectator_id: dana, nickname: "Dana".to_string() });
+        assert!(events.contains(&RoomEvent::SpectatorJoined { spectator_id: eve, nickname: "Eve".to_string() }));
+    }
+
+    #[test]
+    fn test_spectator_sessions() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        let (_, dana) = manager.join_as_spectator(&code, "Dana".to_string()).unwrap();
+        let spectator = Participant::Spectator { spectator_id: dana };
+        let token = manager.issue_session(id, spectator).unwrap();
+        
+        assert_eq!(manager.authenticate_spectator(&id, &token).unwrap(), dana);
+        assert!(matches!(manager.authenticate_player(&id, &token), Err(RoomError::Forbidden(_))));
+        assert!(matches!(manager.authorize_host(&id, &token), Err(RoomError::Forbidden(_))));
+        assert!(matches!(
+            manager.rejoin_room(&code, Rejoin::ResumeToken(token.clone())),
+            Err(RoomError::Forbidden(_))
+        ));
+        
+        manager.leave_as_spectator(id, dana).unwrap();
+        assert!(matches!(manager.authenticate(&id, &token), Err(RoomError::Unauthorized)));
+        assert!(matches!(manager.leave_as_spectator(id, dana), Err(RoomError::SpectatorNotFound(_, _))));
+        assert!(matches!(manager.issue_session(id, spectator), Err(RoomError::SpectatorNotFound(_, _))));
+        assert!(manager.get_room(&id).is_some());
+    }
+
+    #[test]
+    fn test_audience_votes() {
+        let mut manager = RoomManager::new();
+        let (id, players) = revealing_room(&mut manager);
+        let code = manager.get_room(&id).unwrap().code.clone();
+        let (_, dana) = manager.join_as_spectator(&code, "Dana".to_string()).unwrap();
+        manager.next_stage(&id).unwrap();
+        manager.next_stage(&id).unwrap();
+        manager.take_events();
+        
+        manager.submit_audience_votes(&id, dana, HashMap::from([(players[0], 5)])).unwrap();
+        let stranger = SpectatorId::new();
+        assert!(matches!(
+            manager.submit_audience_votes(&id, stranger, HashMap::new()),
+            Err(RoomError::SpectatorNotFound(_, _))
+        ));
+        assert_eq!(manager.take_events(), vec![(id, RoomEvent::AudienceVoteReceived { spectator_id: dana })]);
+        
+        let game = manager.get_room(&id).unwrap().game.as_ref().unwrap();
+        assert_eq!(game.stage, GameStage::Voting);
+        let scores = game.calculate_scores();
+        assert!(scores.players.is_empty());
+        assert_eq!(scores.audience[&players[0]], 5.0);
+    }
+
+    #[test]
+    fn test_late_join_is_opt_in() {
+        let mut manager = RoomManager::new();
+        let (id, players) = started_room(&mut manager);
+        let code = manager.get_room(&id).unwrap().code.clone();
+        
+        let result = manager.join_room(&code, "Carol".to_string(), AvatarId::default());
+        assert!(matches!(result, Err(RoomError::AlreadyStarted(_))));
+        
+        let game = manager.get_room_mut(&id).unwrap().game.as_mut().unwrap();
+        game.settings.allow_late_join = true;
+        manager.take_events();
+        let (_, carol) = manager.join_room(&code, "Carol".to_string(), AvatarId::default()).unwrap();
+        
+        let room = manager.get_room(&id).unwrap();
+        let game = room.game.as_ref().unwrap();
+        assert_eq!(room.player_count(), 3);
+        assert_eq!(game.pending_players, vec![carol]);
+        let object = &game.player_starting_objects[&carol];
+        assert!(players.iter().all(|p| &game.player_starting_objects[p] != object));
+        assert!(matches!(manager.take_events()[..], [(_, RoomEvent::PlayerJoined { .. })]));
+        
+        // Turns are over: nobody else gets in
+        manager.next_stage(&id).unwrap();
+        let result = manager.join_room(&code, "Dave".to_string(), AvatarId::default());
+        assert!(matches!(result, Err(RoomError::AlreadyStarted(_))));
+        assert!(manager.get_room(&id).unwrap().game.as_ref().unwrap().players_in_order.contains(&carol));
+    }
+
+    #[test]
+    fn test_late_join_without_starting_objects_leaves_no_trace() {
+        let mut manager = RoomManager::new();
+        manager.set_decks(vec![tiny_deck()]).unwrap();
+        let settings = GameSettings { decks: vec!["tiny".to_string()], allow_late_join: true, ..GameSettings::default() };
+        manager.set_default_settings(settings).unwrap();
+        let (id, code) = manager.create_room();
+        let mut players = Vec::new();
+        for name in ["Alice", "Bob"] {
+            players.push(manager.join_room(&code, name.to_string(), AvatarId::default()).unwrap().1);
+        }
+        manager.start_game(&id).unwrap();
+        manager.next_stage(&id).unwrap();
+        
+        // Six more take the rest of the deck's eight objects; two then leave
+        for i in 0..6 {
+            players.push(manager.join_room(&code, format!("Late {}", i), AvatarId::default()).unwrap().1);
+        }
+        manager.leave_room(id, players[6]).unwrap();
+        manager.leave_room(id, players[7]).unwrap();
+        manager.take_events();
+        let logged = manager.game_log(&id).unwrap().len();
+        
+        let result = manager.join_room(&code, "Zed".to_string(), AvatarId::default());
+        assert!(matches!(result, Err(RoomError::Full(_))));
+        assert!(manager.take_events().is_empty());
+        assert_eq!(manager.game_log(&id).unwrap().len(), logged);
+        let room = manager.get_room(&id).unwrap();
+        assert_eq!(room.player_count(), 6);
+        assert!(!room.has_player_with_nickname("Zed"));
+        
+        let replayed = crate::game_log::replay(manager.game_log(&id).unwrap()).unwrap();
+        assert_eq!(serde_json::to_value(&replayed).unwrap(), serde_json::to_value(room).unwrap());
+    }
+
+    #[test]
+    fn test_idle_rooms_expire() {
+        let mut manager = RoomManager::new();
+        let (idle, idle_code) = manager.create_room();
+        let (busy, code) = manager.create_room();
+        let (_, alice) = manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        let joined_at = manager.get_room(&busy).unwrap().last_activity;
+        // Nobody around in either room
+        for id in [idle, busy] {
+            manager.get_room_mut(&id).unwrap().display_connected = false;
+        }
+        manager.get_room_mut(&idle).unwrap().last_activity = joined_at - 100;
+        manager.get_room_mut(&busy).unwrap().find_player_mut(alice).unwrap().connected = false;
+        manager.take_events();
+        
+        let timeouts = IdleTimeouts { lobby: 60, ..IdleTimeouts::default() };
+        assert_eq!(manager.expire_idle_rooms(joined_at, &timeouts), vec![idle]);
+        assert!(manager.get_room(&idle).is_none());
+        assert!(manager.get_room_by_code(&idle_code).is_none());
+        assert!(manager.get_room(&busy).is_some());
+        assert_eq!(manager.take_events(), vec![
+            (idle, RoomEvent::RoomExpired),
+            (idle, RoomEvent::RoomClosed),
+        ]);
+        
+        assert_eq!(manager.expire_idle_rooms(joined_at + 61, &timeouts), vec![busy]);
+    }
+
+    #[test]
+    fn test_heartbeating_lobby_outlives_its_idle_timeout() {
+        let mut manager = RoomManager::new();
+        let (id, code) = manager.create_room();
+        let (_, alice) = manager.join_room(&code, "Alice".to_string(), AvatarId::default()).unwrap();
+        let start = manager.get_room(&id).unwrap().last_activity;
+        let timeouts = IdleTimeouts { lobby: 60, ..IdleTimeouts::default() };
+        let grace = 15;
+        let alice = Participant::Player { player_id: alice };
+        
+        // Waiting for the host: heartbeats only, nothing logged
+        let logged = manager.game_log(&id).unwrap().len();
+        let mut now = start;
+        while now < start + 3 * timeouts.lobby {
+            now += 10;
+            manager.touch(id, Participant::Display, now).unwrap();
+            manager.touch(id, alice, now).unwrap();
+            assert!(manager.sweep_presence(now, grace).is_empty());
+            assert!(manager.expire_idle_rooms(now, &timeouts).is_empty());
+        }
+        assert_eq!(manager.game_log(&id).unwrap().len(), logged);
+        
+        // Once everyone goes quiet the lobby timeout runs from then
+        now += grace + 1;
+        assert_eq!(manager.sweep_presence(now, grace).len(), 2);
+        assert!(manager.expire_idle_rooms(now + timeouts.lobby, &timeouts).is_empty());
+        assert_eq!(manager.expire_idle_rooms(now + timeouts.lobby + 1, &timeouts), vec![id]);
+    }
 }
@@ -0,0 +1,88 @@
+//! Secret session tokens that authenticate clients.
+//!
+//! Room and player IDs are public: every client sees them in room snapshots
+//! and events. A [`SessionToken`] is handed only to the client that created
+//! the room or joined it, and is what the server checks before acting on
+//! anyone's behalf.
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::room::Participant;
+use crate::types::RoomId;
+
+/// Length of a session token in random bytes.
+const TOKEN_BYTES: usize = 32;
+
+/// An unguessable secret identifying one client's session.
+///
+/// `Debug` hides the value so tokens never end up in logs.
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SessionToken(String);
+
+impl SessionToken {
+    /// Generate a new random token (256 bits, hex encoded).
+    pub fn generate() -> Self {
+        let mut bytes = [0u8; TOKEN_BYTES];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        Self(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+    }
+
+    /// Wrap a token presented by a client.
+    pub fn new(token: impl Into<String>) -> Self {
+        Self(token.into())
+    }
+
+    /// Get the token to hand to the client.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for SessionToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SessionToken(..)")
+    }
+}
+
+/// Who a session token belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    /// The room the session is valid for.
+    pub room_id: RoomId,
+
+    /// The display or player the session acts for.
+    pub participant: Participant,
+}
+
+/// How a player proves who they are when rejoining a room.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rejoin {
+    /// The session token from the player's last join or rejoin.
+    ResumeToken(SessionToken),
+
+    /// The player's nickname. Only accepted while that player is
+    /// disconnected, so nobody can take over a seat in use.
+    Nickname(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_are_unique_and_long() {
+        let a = SessionToken::generate();
+        let b = SessionToken::generate();
+        assert_ne!(a, b);
+        assert_eq!(a.as_str().len(), TOKEN_BYTES * 2);
+        assert!(a.as_str().chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn test_debug_hides_token() {
+        let token = SessionToken::generate();
+        assert!(!format!("{:?}", token).contains(token.as_str()));
+    }
+}
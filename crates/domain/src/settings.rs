@@ -0,0 +1,200 @@
+//! Per-room game settings chosen by the host in the lobby.
+
+use crate::content::DEFAULT_PACK_ID;
+use crate::errors::RoomError;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// Fewest players any game can start with; turns and voting need two.
+pub const MIN_PLAYERS: usize = 2;
+
+/// Hard cap on players per room, whatever the settings say.
+pub const MAX_PLAYERS: usize = 8;
+
+/// Most modification options a turn can offer.
+pub const MAX_OPTIONS_PER_TURN: usize = 6;
+
+/// Default length of a player's turn, in seconds.
+pub const DEFAULT_TURN_SECONDS: u64 = 10;
+
+/// Default length of the goal reveal, in seconds.
+pub const DEFAULT_REVEAL_SECONDS: u64 = 10;
+
+/// Default length of the voting stage, in seconds.
+pub const DEFAULT_VOTING_SECONDS: u64 = 60;
+
+/// Longest a turn is held for a disconnected player under
+/// [`DisconnectPolicy::Wait`] before it is skipped, in seconds.
+pub const MAX_WAIT_SECONDS: u64 = 120;
+
+const ROUNDS: RangeInclusive<u32> = 1..=10;
+const TURN_SECONDS: RangeInclusive<u64> = 5..=120;
+const REVEAL_SECONDS: RangeInclusive<u64> = 3..=120;
+const VOTING_SECONDS: RangeInclusive<u64> = 10..=600;
+const OPTIONS_PER_TURN: RangeInclusive<usize> = 2..=MAX_OPTIONS_PER_TURN;
+
+/// What happens when it is a disconnected player's turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisconnectPolicy {
+    /// Skip the turn at once, as if the player had passed.
+    #[default]
+    Skip,
+
+    /// Hold the turn until the player returns, for at most
+    /// [`MAX_WAIT_SECONDS`] before it is skipped.
+    Wait,
+}
+
+/// How a room's game is played.
+///
+/// Missing fields deserialize to their defaults.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameSettings {
+    /// Number of rounds (each player acts once per round).
+    pub rounds: u32,
+
+    /// How long each turn lasts before it is skipped automatically.
+    pub turn_seconds: u64,
+
+    /// How long the goal reveal lasts before turns begin automatically.
+    pub reveal_seconds: u64,
+
+    /// How long voting lasts before results are shown automatically.
+    pub voting_seconds: u64,
+
+    /// Fewest players needed to start.
+    pub min_players: usize,
+
+    /// Most players allowed to join (at most [`MAX_PLAYERS`]).
+    pub max_players: usize,
+
+    /// Number of modification options offered each turn.
+    pub options_per_turn: usize,
+
+    /// Whether disconnected players' turns are skipped or waited for.
+    pub disconnect_policy: DisconnectPolicy,
+
+    /// Whether new players may join while turns are being taken. They get
+    /// their first turn from the next round on.
+    pub allow_late_join: bool,
+
+    /// Ids of the decks (content packs) goals, starting objects and
+    /// modifiers are drawn from.
+    pub decks: Vec<String>,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self {
+            rounds: 3,
+            turn_seconds: DEFAULT_TURN_SECONDS,
+            reveal_seconds: DEFAULT_REVEAL_SECONDS,
+            voting_seconds: DEFAULT_VOTING_SECONDS,
+            min_players: MIN_PLAYERS,
+            max_players: MAX_PLAYERS,
+            options_per_turn: 4,
+            disconnect_policy: DisconnectPolicy::Skip,
+            allow_late_join: false,
+            decks: vec![DEFAULT_PACK_ID.to_string()],
+        }
+    }
+}
+
+impl GameSettings {
+    /// Check every setting is within its allowed range.
+    pub fn validate(&self) -> Result<(), RoomError> {
+        check("rounds", self.rounds, ROUNDS)?;
+        check("turn_seconds", self.turn_seconds, TURN_SECONDS)?;
+        check("reveal_seconds", self.reveal_seconds, REVEAL_SECONDS)?;
+        check("voting_seconds", self.voting_seconds, VOTING_SECONDS)?;
+        check("max_players", self.max_players, MIN_PLAYERS..=MAX_PLAYERS)?;
+        check("min_players", self.min_players, MIN_PLAYERS..=self.max_players)?;
+        check("options_per_turn", self.options_per_turn, OPTIONS_PER_TURN)?;
+        if self.decks.is_empty() {
+            return Err(RoomError::InvalidSettings("decks must name at least one deck".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Range of player counts a game can start with.
+    pub fn player_range(&self) -> RangeInclusive<usize> {
+        self.min_players..=self.max_players
+    }
+}
+
+fn check<T>(name: &str, value: T, range: RangeInclusive<T>) -> Result<(), RoomError>
+where
+    T: PartialOrd + std::fmt::Display,
+{
+    if range.contains(&value) {
+        Ok(())
+    } else {
+        Err(RoomError::InvalidSettings(format!(
+            "{} must be between {} and {} (got {})",
+            name,
+            range.start(),
+            range.end(),
+            value
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_are_valid() {
+        let settings = GameSettings::default();
+        assert!(settings.validate().is_ok());
+        assert_eq!(settings.rounds, 3);
+        assert_eq!(settings.player_range(), 2..=8);
+    }
+
+    #[test]
+    fn test_out_of_range_settings_rejected() {
+        let settings = GameSettings { rounds: 0, ..GameSettings::default() };
+        let err = settings.validate().unwrap_err();
+        assert!(err.to_string().contains("rounds"));
+
+        let settings = GameSettings { max_players: 9, ..GameSettings::default() };
+        assert!(settings.validate().is_err());
+
+        let settings = GameSettings { options_per_turn: 1, ..GameSettings::default() };
+        assert!(settings.validate().is_err());
+
+        let settings = GameSettings { decks: Vec::new(), ..GameSettings::default() };
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_min_players_cannot_exceed_max() {
+        let settings = GameSettings { min_players: 5, max_players: 4, ..GameSettings::default() };
+        let err = settings.validate().unwrap_err();
+        assert!(err.to_string().contains("min_players"));
+    }
+
+    #[test]
+    fn test_solo_games_rejected() {
+        let settings = GameSettings { min_players: 1, ..GameSettings::default() };
+        let err = settings.validate().unwrap_err();
+        assert!(err.to_string().contains("min_players"));
+
+        let settings = GameSettings { min_players: 1, max_players: 1, ..GameSettings::default() };
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_missing_fields_use_defaults() {
+        let settings: GameSettings = serde_json::from_str(r#"{"rounds": 5}"#).expect("Should deserialize");
+        assert_eq!(settings.rounds, 5);
+        assert_eq!(settings.turn_seconds, DEFAULT_TURN_SECONDS);
+        assert_eq!(settings.disconnect_policy, DisconnectPolicy::Skip);
+        assert_eq!(settings.decks, vec![DEFAULT_PACK_ID.to_string()]);
+        
+        let settings: GameSettings = serde_json::from_str(r#"{"disconnect_policy": "wait"}"#).expect("Should deserialize");
+        assert_eq!(settings.disconnect_policy, DisconnectPolicy::Wait);
+    }
+}
@@ -0,0 +1,55 @@
+//! Spectator entity: audience members watching a room.
+
+use crate::types::SpectatorId;
+use serde::{Deserialize, Serialize};
+
+/// Someone watching a room without playing.
+///
+/// Spectators can join by code at any time, see the same room state as the
+/// display and may rate players during voting, but they never take a seat,
+/// a turn, or count towards the player cap.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Spectator {
+    /// Unique identifier for this spectator.
+    pub id: SpectatorId,
+
+    /// Spectator's chosen nickname (unique among the room's spectators).
+    pub nickname: String,
+}
+
+impl Spectator {
+    /// Create a new spectator with the given nickname.
+    pub fn new(nickname: String) -> Self {
+        Self {
+            id: SpectatorId::new(),
+            nickname,
+        }
+    }
+
+    /// Check if this spectator goes by the given nickname.
+    pub fn matches_nickname(&self, nickname: &str) -> bool {
+        self.nickname == nickname
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spectator_creation() {
+        let a = Spectator::new("Dana".to_string());
+        let b = Spectator::new("Dana".to_string());
+        assert!(a.matches_nickname("Dana"));
+        assert!(!a.matches_nickname("dana"));
+        assert_ne!(a.id, b.id);
+    }
+
+    #[test]
+    fn test_spectator_serialization() {
+        let spectator = Spectator::new("Eve".to_string());
+        let json = serde_json::to_string(&spectator).expect("Should serialize");
+        let deserialized: Spectator = serde_json::from_str(&json).expect("Should deserialize");
+        assert_eq!(deserialized, spectator);
+    }
+}
@@ -73,6 +73,39 @@ impl fmt::Display for PlayerId {
     }
 }
 
+/// Unique identifier for a spectator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SpectatorId(Uuid);
+
+impl SpectatorId {
+    /// Create a new random SpectatorId.
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    /// Get the inner UUID.
+    pub fn as_uuid(&self) -> &Uuid {
+        &self.0
+    }
+
+    /// Create a SpectatorId from a string (for deserializing from URLs/JSON).
+    pub fn from_string(s: &str) -> Result<Self, uuid::Error> {
+        Ok(Self(Uuid::parse_str(s)?))
+    }
+}
+
+impl Default for SpectatorId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for SpectatorId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 /// Avatar identifier (0-9 for initial set of 10 avatars).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct AvatarId(u8);
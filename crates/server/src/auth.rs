@@ -0,0 +1,112 @@
+//! Bearer-token authentication of mutating requests.
+//!
+//! `POST /rooms`, `/join` and `/rejoin` hand out a session token. Every route
+//! that changes a room expects it back as `Authorization: Bearer <token>`;
+//! the player a route acts for is the one the token belongs to, never an ID
+//! from the request body.
+
+use crate::AppError;
+use axum::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
+use big_picture_domain::{RoomError, SessionToken};
+
+/// The session token of the request's `Authorization: Bearer` header.
+pub(crate) struct Bearer(pub SessionToken);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Bearer {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(|token| Bearer(SessionToken::new(token.trim())))
+            .ok_or(AppError::Room(RoomError::Unauthorized))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{push, router, AppState};
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use axum::Router;
+    use big_picture_domain::RoomManager;
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+    use tokio::sync::RwLock;
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        let state = AppState {
+            room_manager: Arc::new(RwLock::new(RoomManager::new())),
+            events: Arc::new(push::EventHub::default()),
+            image_base_url: None,
+        };
+        router(state, None)
+    }
+
+    async fn call(app: &Router, method: Method, uri: &str, token: Option<&str>, body: Value) -> (StatusCode, Value) {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Content-Type", "application/json");
+        if let Some(token) = token {
+            request = request.header("Authorization", format!("Bearer {}", token));
+        }
+        let request = request.body(Body::from(body.to_string())).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+    }
+
+    /// Create a room, returning its ID, code and display token.
+    async fn create_room(app: &Router) -> (String, String, String) {
+        let (status, room) = call(app, Method::POST, "/rooms", None, Value::Null).await;
+        assert_eq!(status, StatusCode::OK);
+        let field = |name: &str| room[name].as_str().unwrap().to_string();
+        (field("room_id"), field("room_code"), field("session_token"))
+    }
+
+    #[tokio::test]
+    async fn test_missing_token_is_unauthorized() {
+        let app = app();
+        let (room_id, _, _) = create_room(&app).await;
+
+        let uri = format!("/rooms/{}/start", room_id);
+        let (status, _) = call(&app, Method::POST, &uri, None, Value::Null).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_spectator_cannot_act_as_player() {
+        let app = app();
+        let (room_id, code, _) = create_room(&app).await;
+        let uri = format!("/rooms/{}/spectate", code);
+        let (status, spectator) = call(&app, Method::POST, &uri, None, json!({ "nickname": "Dana" })).await;
+        assert_eq!(status, StatusCode::OK);
+        let token = spectator["session_token"].as_str().unwrap();
+
+        let uri = format!("/rooms/{}/action", room_id);
+        let (status, body) = call(&app, Method::POST, &uri, Some(token), json!({ "option_index": 0 })).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert!(body.to_string().contains("only players"), "{}", body);
+    }
+
+    #[tokio::test]
+    async fn test_token_for_another_room_is_unauthorized() {
+        let app = app();
+        let (_, _, token) = create_room(&app).await;
+        let (other_room, _, _) = create_room(&app).await;
+
+        let uri = format!("/rooms/{}/start", other_room);
+        let (status, _) = call(&app, Method::POST, &uri, Some(&token), Value::Null).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,86 @@
+//! Content pack loading.
+//!
+//! Game content (animals, objects, locations and modifiers) is read from the
+//! JSON packs in a directory at startup, so prompts can change without a
+//! rebuild. Each pack is a deck hosts can pick for their room. Packs that
+//! fail to parse or validate are logged and skipped; the compiled-in
+//! `default` deck is always available.
+
+use big_picture_domain::{ContentError, ContentPack};
+use std::path::{Path, PathBuf};
+
+/// Default directory content packs are read from.
+const DEFAULT_CONTENT_DIR: &str = "content";
+
+/// Read `CONTENT_DIR` (default `content`).
+pub fn dir_from_env() -> PathBuf {
+    PathBuf::from(std::env::var("CONTENT_DIR").unwrap_or_else(|_| DEFAULT_CONTENT_DIR.to_string()))
+}
+
+/// Load every valid `*.json` pack in `dir`, in file name order.
+///
+/// A pack whose id was already loaded is skipped.
+pub fn load_dir(dir: &Path) -> Vec<ContentPack> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            tracing::warn!("No content packs loaded from {}: {}", dir.display(), e);
+            return Vec::new();
+        }
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    let mut packs: Vec<ContentPack> = Vec::new();
+    for path in paths {
+        let json = match std::fs::read_to_string(&path) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::error!("Failed to read content pack {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        match ContentPack::from_json(&json) {
+            Ok(pack) if packs.iter().any(|p| p.id == pack.id) => {
+                tracing::error!("Skipping {}: {}", path.display(), ContentError::DuplicatePack(pack.id));
+            }
+            Ok(pack) => {
+                tracing::info!("Loaded content pack '{}' v{} from {}", pack.id, pack.version, path.display());
+                packs.push(pack);
+            }
+            Err(e) => tracing::error!("Skipping {}: {}", path.display(), e),
+        }
+    }
+    packs
+}
+
+/// Read `DEFAULT_DECKS` (comma-separated deck ids, default `default`), the
+/// decks new rooms start with.
+///
+/// Ids not among `available` are dropped; if none are left the built-in
+/// deck is used.
+pub fn default_decks_from_env(available: &[ContentPack]) -> Vec<String> {
+    let builtin = ContentPack::builtin().id;
+    let ids = std::env::var("DEFAULT_DECKS").unwrap_or_else(|_| builtin.clone());
+    let decks: Vec<String> = ids
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .filter(|id| {
+            let known = available.iter().any(|deck| deck.id == *id);
+            if !known {
+                tracing::warn!("Deck '{}' not found, leaving it out of the default decks", id);
+            }
+            known
+        })
+        .map(str::to_string)
+        .collect();
+    if decks.is_empty() {
+        vec![builtin]
+    } else {
+        decks
+    }
+}
@@ -0,0 +1,72 @@
+//! Server-Sent Events fallback for room updates.
+//!
+//! `GET /rooms/:room_id/events` streams the same JSON messages as the WebSocket
+//! push channel for clients behind proxies that block upgrades. Every event
+//! carries its room sequence number as the SSE `id`, so a browser
+//! `EventSource` that reconnects with `Last-Event-ID` is sent only the events
+//! it missed. If those are no longer buffered (or no ID is given), the stream
+//! starts with a snapshot instead.
+//!
+//! A subscriber that falls too far behind is disconnected and resumes the
+//! same way.
+
+use crate::push::{snapshot_json, PushedEvent};
+use crate::{AppError, AppState};
+use axum::extract::{Path, State};
+use axum::http::HeaderMap;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use big_picture_domain::{RoomError, RoomId};
+use futures_util::stream::{self, Stream, StreamExt};
+use std::convert::Infallible;
+use tokio::sync::broadcast;
+
+/// GET /rooms/:room_id/events - Subscribe to room updates as Server-Sent Events.
+pub async fn room_events(
+    State(state): State<AppState>,
+    Path(room_id_str): Path<String>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let room_id = RoomId::from_string(&room_id_str)
+        .map_err(|_| AppError::InvalidRoomId)?;
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok());
+
+    let manager = state.room_manager.read().await;
+    if manager.get_room(&room_id).is_none() {
+        return Err(RoomError::RoomNotFound.into());
+    }
+
+    // Subscribe under the lock so the snapshot and the stream line up
+    let subscription = state.events.subscribe(room_id, last_event_id);
+    let initial = match subscription.missed {
+        Some(missed) => {
+            tracing::debug!("Resuming room {} stream after event {:?}", room_id, last_event_id);
+            missed.iter().map(sse_event).collect()
+        }
+        None => {
+            let snapshot = snapshot_json(&state, &manager, room_id);
+            vec![Event::default().id(subscription.last_id.to_string()).data(snapshot)]
+        }
+    };
+    drop(manager);
+
+    let live = stream::unfold(subscription.events, move |mut events| async move {
+        match events.recv().await {
+            Ok(event) => Some((sse_event(&event), events)),
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                tracing::debug!("Event stream of room {} lagged by {} events", room_id, skipped);
+                None
+            }
+            Err(broadcast::error::RecvError::Closed) => None,
+        }
+    });
+
+    let stream = stream::iter(initial).chain(live).map(Ok);
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+fn sse_event(event: &PushedEvent) -> Event {
+    Event::default().id(event.id.to_string()).data(&*event.json)
+}
@@ -0,0 +1,47 @@
+//! Idle room expiry.
+//!
+//! Rooms nobody closes (a TV left on the lobby, a game abandoned on the
+//! results screen) would otherwise live forever and keep their codes. A
+//! background sweeper closes rooms without activity for longer than their
+//! stage allows; clients still connected get `room_expired` and then
+//! `room_closed`.
+
+use crate::stage_timer::unix_now;
+use crate::AppState;
+use big_picture_domain::IdleTimeouts;
+use std::time::Duration;
+
+/// How often the sweeper looks for idle rooms.
+const SWEEP: Duration = Duration::from_secs(30);
+
+/// Read `ROOM_IDLE_LOBBY_SECONDS`, `ROOM_IDLE_GAME_SECONDS` and
+/// `ROOM_IDLE_FINISHED_SECONDS`, keeping the defaults for any unset.
+pub fn timeouts_from_env() -> IdleTimeouts {
+    let defaults = IdleTimeouts::default();
+    let seconds = |name: &str, default: u64| {
+        std::env::var(name)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default)
+    };
+    IdleTimeouts {
+        lobby: seconds("ROOM_IDLE_LOBBY_SECONDS", defaults.lobby),
+        in_game: seconds("ROOM_IDLE_GAME_SECONDS", defaults.in_game),
+        finished: seconds("ROOM_IDLE_FINISHED_SECONDS", defaults.finished),
+    }
+}
+
+/// Close rooms idle for longer than `timeouts` allow.
+pub fn spawn_sweeper(state: AppState, timeouts: IdleTimeouts) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SWEEP);
+        loop {
+            interval.tick().await;
+            let mut manager = state.room_manager.write().await;
+            for room_id in manager.expire_idle_rooms(unix_now(), &timeouts) {
+                tracing::info!("Room {} expired after going idle", room_id);
+            }
+            state.publish(&mut manager);
+        }
+    });
+}
@@ -7,57 +7,99 @@
 //! - Axum web framework with tokio async runtime
 //! - In-memory state management (RoomManager)
 //! - REST endpoints for lobby and game operations
+//! - WebSocket push of room snapshots and change events (see `push`), with a
+//!   Server-Sent Events fallback (see `event_stream`)
 //!
 //! ## Endpoints
 //!
+//! Creating, joining and rejoining a room return a `session_token`. Every
+//! other `POST`/`PUT` needs it as `Authorization: Bearer <token>` (see `auth`).
+//! The display that created the room is its host; only the host may change
+//! settings, start, advance or close the game. The display is not a player.
+//! When the host disconnects or leaves, the role passes to the player
+//! connected the longest, and back to the display when it reconnects.
+//!
 //! - `GET /` - Health check
-//! - `POST /rooms` - Create new room
+//! - `GET /decks` - Decks (content packs) the host can pick in the lobby
+//! - `POST /rooms` - Create new room (`?seed=` reproduces a game's draws)
 //! - `POST /rooms/:code/join` - Join room
-//! - `POST /rooms/:room_id/leave` - Leave room
-//! - `POST /rooms/:code/rejoin` - Rejoin room
-//! - `POST /rooms/:room_id/start` - Start game ("All is in!")
+//! - `POST /rooms/:code/spectate` - Watch room as a spectator, at any stage
+//! - `POST /rooms/:room_id/leave` - Leave room (the display's token marks it disconnected)
+//! - `POST /rooms/:room_id/reconnect` - Mark the token's display or player connected again
+//! - `POST /rooms/:room_id/heartbeat` - Keep the token's display or player connected (see `presence`)
+//! - `POST /rooms/:room_id/close` - Close room for everyone (host only)
+//! - `POST /rooms/:code/rejoin` - Rejoin room with a `resume_token` (the last session token), or by
+//!   nickname while that player is disconnected
+//! - `PUT /rooms/:room_id/settings` - Change game settings, including the decks (host only, lobby only)
+//! - `POST /rooms/:room_id/start` - Start game ("All is in!", host only)
+//! - `POST /rooms/:room_id/ready` - Acknowledge the goal reveal
+//! - `POST /rooms/:room_id/next` - Host only: skip to the next stage
+//! - `POST /rooms/:room_id/audience-votes` - Spectators' votes, scored apart from the players'
 //! - `GET /rooms/:room_id` - Get room state
+//! - `GET /rooms/:room_id/log` - Event log of the room, replayable with `big_picture_domain::replay`
+//! - `GET /rooms/:room_id/ws` - WebSocket: room snapshot, then change events (`?token=` keeps the caller connected)
+//! - `GET /rooms/:room_id/events` - Server-Sent Events version of the above (honours `Last-Event-ID`)
+//! - `GET /images/:image_id` - Generated images (procedural backend only)
+//!
+//! ## Configuration
+//!
+//! - `IMAGE_BACKEND` - `procedural` (default, offline SVG renderer) or `placeholder`
+//! - `IMAGE_DIR` - Where the procedural backend writes images (default `generated_images`)
+//! - `TURN_SECONDS` - Default turn length before the server skips the current player (default 10)
+//! - `REVEAL_SECONDS` - Default goal reveal length before turns begin (default 10)
+//! - `VOTING_SECONDS` - Default voting length before results are shown (default 60)
+//! - `STATE_FILE` - Where rooms are saved across restarts (default `room_state.json`, empty disables)
+//! - `SAVE_SECONDS` - Interval between periodic saves (default 15)
+//! - `PRESENCE_GRACE_SECONDS` - Silence before a display or player is marked disconnected (default 15)
+//! - `ROOM_IDLE_LOBBY_SECONDS`, `ROOM_IDLE_GAME_SECONDS`, `ROOM_IDLE_FINISHED_SECONDS` - Inactivity
+//!   before a room in the lobby, in a game or showing results expires (see `expiry`; defaults
+//!   1800, 1800 and 600)
+//! - `CONTENT_DIR` - Directory of JSON content packs (decks) loaded at startup (see `content`; default `content`)
+//! - `DEFAULT_DECKS` - Comma-separated ids of the decks new rooms start with (default `default`)
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::{Html, IntoResponse},
-    routing::{get, post},
+    routing::{get, post, put},
     Json, Router,
 };
 use big_picture_domain::{
-    AvatarId, JoinError, RoomError, RoomManager, RoomId, PlayerId,
+    AvatarId, GameEvaluation, GameSettings, ImageGenerator, ImageId, JoinError, LoggedEvent, ModifierOption, Participant,
+    PlaceholderImageGenerator, ProceduralImageGenerator, Rejoin, RoomError, RoomManager, RoomId, RoomState, PlayerId,
+    SessionToken,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use auth::Bearer;
 use tower_http::cors::{Any, CorsLayer};
+use tower_http::services::ServeDir;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+mod auth;
+mod content;
+mod event_stream;
+mod expiry;
+mod persistence;
+mod presence;
+mod push;
+mod stage_timer;
+
 /// Shared application state.
 #[derive(Clone)]
 struct AppState {
     room_manager: Arc<RwLock<RoomManager>>,
+    
+    /// WebSocket subscribers per room.
+    events: Arc<push::EventHub>,
+    
+    /// URL prefix generated images are served from, if the backend writes files.
+    image_base_url: Option<&'static str>,
 }
 
-#[tokio::main]
-async fn main() {
-    // Initialize tracing
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "big_picture_server=info,tower_http=debug".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
-
-    tracing::info!("Big Picture Server starting...");
-
-    // Initialize shared state
-    let state = AppState {
-        room_manager: Arc::new(RwLock::new(RoomManager::new())),
-    };
-
+/// Build the HTTP routes, serving `image_dir` when the backend writes files.
+fn router(state: AppState, image_dir: Option<std::path::PathBuf>) -> Router {
     // Configure CORS for cross-origin requests from Godot client
     let cors = CorsLayer::new()
         .allow_origin(Any)
@@ -65,19 +107,127 @@ async fn main() {
         .allow_headers(Any);
 
     // Build router
-    let app = Router::new()
+    let mut app = Router::new()
         .route("/", get(health_check))
+        .route("/decks", get(list_decks))
         .route("/rooms", post(create_room))
         .route("/rooms/:code/join", post(join_room))
+        .route("/rooms/:code/spectate", post(spectate_room))
         .route("/rooms/:room_id/leave", post(leave_room))
+        .route("/rooms/:room_id/reconnect", post(reconnect))
+        .route("/rooms/:room_id/heartbeat", post(heartbeat))
+        .route("/rooms/:room_id/close", post(close_room))
         .route("/rooms/:code/rejoin", post(rejoin_room))
+        .route("/rooms/:room_id/settings", put(update_settings))
         .route("/rooms/:room_id/start", post(start_game))
+        .route("/rooms/:room_id/ready", post(acknowledge_reveal))
         .route("/rooms/:room_id/next", post(next_stage))
         .route("/rooms/:room_id/action", post(submit_action))
         .route("/rooms/:room_id/votes", post(submit_votes))
+        .route("/rooms/:room_id/audience-votes", post(submit_audience_votes))
         .route("/rooms/:room_id", get(get_room_state))
-        .layer(cors)
-        .with_state(state);
+        .route("/rooms/:room_id/log", get(get_room_log))
+        .route("/rooms/:room_id/ws", get(push::room_socket))
+        .route("/rooms/:room_id/events", get(event_stream::room_events));
+    
+    if let Some(dir) = image_dir {
+        app = app.nest_service(IMAGE_ROUTE, ServeDir::new(dir));
+    }
+    
+    app.layer(cors).with_state(state)
+}
+
+/// Path prefix for images written by the procedural backend.
+const IMAGE_ROUTE: &str = "/images";
+
+/// Select the image backend from `IMAGE_BACKEND` / `IMAGE_DIR`.
+///
+/// Returns the generator and, for file-based backends, the directory to serve.
+fn image_generator_from_env() -> (Arc<dyn ImageGenerator>, Option<std::path::PathBuf>) {
+    let backend = std::env::var("IMAGE_BACKEND").unwrap_or_else(|_| "procedural".to_string());
+    match backend.as_str() {
+        "placeholder" => (Arc::new(PlaceholderImageGenerator), None),
+        other => {
+            if other != "procedural" {
+                tracing::warn!("Unknown IMAGE_BACKEND '{}', falling back to procedural", other);
+            }
+            let dir = std::env::var("IMAGE_DIR").unwrap_or_else(|_| "generated_images".to_string());
+            let dir = std::path::PathBuf::from(dir);
+            (Arc::new(ProceduralImageGenerator::new(dir.clone())), Some(dir))
+        }
+    }
+}
+
+/// Default settings of new rooms, from `TURN_SECONDS` / `REVEAL_SECONDS` / `VOTING_SECONDS`.
+fn default_settings_from_env() -> GameSettings {
+    let seconds = |name: &str| std::env::var(name).ok().and_then(|v| v.parse().ok());
+    let defaults = GameSettings::default();
+    GameSettings {
+        turn_seconds: seconds("TURN_SECONDS").unwrap_or(defaults.turn_seconds),
+        reveal_seconds: seconds("REVEAL_SECONDS").unwrap_or(defaults.reveal_seconds),
+        voting_seconds: seconds("VOTING_SECONDS").unwrap_or(defaults.voting_seconds),
+        ..defaults
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    // Initialize tracing
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "big_picture_server=info,tower_http=debug".into()),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    tracing::info!("Big Picture Server starting...");
+
+    // Initialize shared state
+    let (image_generator, image_dir) = image_generator_from_env();
+    tracing::info!("Using image generator: {:?}", image_generator);
+    
+    let mut room_manager = RoomManager::with_image_generator(image_generator);
+    if let Err(e) = room_manager.set_decks(content::load_dir(&content::dir_from_env())) {
+        tracing::warn!("Ignoring loaded content packs: {}", e);
+    }
+    let default_settings = GameSettings {
+        decks: content::default_decks_from_env(room_manager.decks()),
+        ..default_settings_from_env()
+    };
+    if let Err(e) = room_manager.set_default_settings(default_settings) {
+        tracing::warn!("Ignoring configured game settings: {}", e);
+    }
+    
+    let state_file = persistence::state_file_from_env();
+    if let Some(snapshot) = state_file.as_deref().and_then(persistence::load) {
+        tracing::info!("Restored {} rooms from saved state", snapshot.rooms.len());
+        room_manager.restore(snapshot);
+    }
+    
+    let state = AppState {
+        room_manager: Arc::new(RwLock::new(room_manager)),
+        events: Arc::new(push::EventHub::default()),
+        image_base_url: image_dir.as_ref().map(|_| IMAGE_ROUTE),
+    };
+    
+    // Restart the stage timers of restored games
+    {
+        let manager = state.room_manager.read().await;
+        for room_id in manager.room_ids() {
+            if manager.get_room(&room_id).is_some_and(|room| room.state == RoomState::InGame) {
+                stage_timer::spawn(state.clone(), room_id);
+            }
+        }
+    }
+    
+    if let Some(path) = state_file.clone() {
+        persistence::spawn_autosave(state.clone(), path);
+    }
+    presence::spawn_sweeper(state.clone(), presence::grace_from_env());
+    expiry::spawn_sweeper(state.clone(), expiry::timeouts_from_env());
+
+    let app = router(state.clone(), image_dir);
 
     // Start server
     let listener = tokio::net::TcpListener::bind("0.0.0.0:3000")
@@ -86,7 +236,18 @@ async fn main() {
     
     tracing::info!("Server ready at http://localhost:3000");
     
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app)
+        .with_graceful_shutdown(persistence::shutdown_signal())
+        .await
+        .unwrap();
+    
+    // Save once more so nothing since the last periodic save is lost
+    if let Some(path) = state_file {
+        match persistence::save(&state, &path).await {
+            Ok(()) => tracing::info!("Saved rooms to {}", path.display()),
+            Err(e) => tracing::error!("Failed to save rooms to {}: {}", path.display(), e),
+        }
+    }
 }
 
 /// Health check endpoint.
@@ -141,7 +302,7 @@ async fn health_check() -> Html<&'static str> {
             color: #fff;
             font-size: 0.9rem;
         }
-        input {
+        input, select {
             width: 100%;
             padding: 0.8rem;
             border-radius: 8px;
@@ -152,7 +313,7 @@ async fn health_check() -> Html<&'static str> {
             box-sizing: border-box;
             margin-bottom: 1rem;
         }
-        input:focus {
+        input:focus, select:focus {
             outline: none;
             border-color: #e94560;
         }
@@ -266,6 +427,13 @@ async fn health_check() -> Html<&'static str> {
             margin-top: 2rem;
         }
         .hidden { display: none !important; }
+        .scene-image {
+            display: block;
+            width: 100%;
+            border-radius: 12px;
+            border: 2px solid #0f3460;
+            margin: 0.5rem 0;
+        }
         .option-btn {
             display: block;
             width: 100%;
@@ -283,6 +451,13 @@ async fn health_check() -> Html<&'static str> {
             background: #4ecca3;
             color: #1a1a2e;
         }
+        .option-category {
+            display: block;
+            font-size: 0.75rem;
+            text-transform: uppercase;
+            letter-spacing: 0.05em;
+            opacity: 0.7;
+        }
         .voting-controls button {
             background: none;
             border: 1px solid #4ecca3;
@@ -340,6 +515,7 @@ async fn health_check() -> Html<&'static str> {
             </div>
             <div class="actions">
                 <button class="btn-secondary" onclick="joinPlayer()">Join Game</button>
+                <button class="btn-outline" onclick="watchRoom()">Watch as Audience</button>
                 <button class="btn-outline" onclick="showView('selection')">Back</button>
             </div>
         </div>
@@ -349,6 +525,32 @@ async fn health_check() -> Html<&'static str> {
             <p id="lobby-status">Waiting for players...</p>
             <div class="room-code-display" id="display-code">------</div>
             <ul id="player-list"></ul>
+            <p id="spectator-list" style="font-size: 0.9rem;"></p>
+            <p id="settings-summary" style="font-size: 0.9rem;"></p>
+            <!-- Host Only -->
+            <div id="settings-form" class="form-group hidden">
+                <label for="setting-rounds">Rounds</label>
+                <input type="number" id="setting-rounds" min="1" max="10">
+                <label for="setting-turn-seconds">Seconds per Turn</label>
+                <input type="number" id="setting-turn-seconds" min="5" max="120">
+                <label for="setting-voting-seconds">Seconds for Voting</label>
+                <input type="number" id="setting-voting-seconds" min="10" max="600">
+                <label for="setting-options">Options per Turn</label>
+                <input type="number" id="setting-options" min="2" max="6">
+                <label for="setting-disconnect-policy">Disconnected Players' Turns</label>
+                <select id="setting-disconnect-policy">
+                    <option value="skip">Skip</option>
+                    <option value="wait">Wait for them</option>
+                </select>
+                <label for="setting-late-join">New Players Mid-Game</label>
+                <select id="setting-late-join">
+                    <option value="false">Wait for the next game</option>
+                    <option value="true">Join from the next round</option>
+                </select>
+                <label for="setting-decks">Decks (hold Ctrl/Cmd to pick several)</label>
+                <select id="setting-decks" multiple></select>
+                <button class="btn-outline" onclick="saveSettings()">Save Settings</button>
+            </div>
             <div class="actions">
                 <button id="btn-start-game" class="btn-primary hidden" onclick="startGame()">Start Game</button>
                 <button class="btn-quit" onclick="quitRoom()">Quit Room</button>
@@ -361,6 +563,7 @@ async fn health_check() -> Html<&'static str> {
             <div id="game-header" style="margin-bottom: 1rem; border-bottom: 1px solid #4ecca3; padding-bottom: 1rem;">
                 <small style="color: #888; text-transform: uppercase; letter-spacing: 1px;">Communal Goal</small>
                 <div class="goal-display" id="display-goal" style="margin: 0.5rem 0; padding: 1rem; font-size: 1.1rem;">...</div>
+                <img id="display-goal-image" class="scene-image hidden" alt="Goal image">
             </div>
 
             <!-- Reveal Stage -->
@@ -380,16 +583,19 @@ async fn health_check() -> Html<&'static str> {
                         <div id="display-starting-object" style="font-size: 1.5rem; color: #4ecca3; margin: 1rem 0;">...</div>
                     </div>
                     <p>Look at the TV for the goal!</p>
+                    <button id="btn-ready" class="btn-primary" onclick="acknowledgeReveal()">I'm Ready!</button>
+                    <p id="ready-status" class="hidden">Ready! Waiting for the others...</p>
                 </div>
             </div>
 
             <!-- Turn Stage -->
             <div id="stage-turn" class="hidden">
                 <h3 id="turn-status">Round 1</h3>
-                <div id="turn-timer" style="font-size: 2rem; color: #e94560; font-weight: bold;">10</div>
+                <div id="turn-timer" style="font-size: 2rem; color: #e94560; font-weight: bold;"></div>
                 
                 <!-- TV Only -->
                 <div id="tv-turn-info" class="hidden">
+                    <img id="tv-current-image" class="scene-image hidden" alt="Current image">
                     <div class="starting-object-box">
                         <h3>Current Player: <span id="tv-current-player">...</span></h3>
                         <div id="tv-current-object" style="font-size: 1.5rem; color: #4ecca3;">...</div>
@@ -413,6 +619,7 @@ async fn health_check() -> Html<&'static str> {
             <div id="stage-voting" class="hidden">
                 <h2>Vote for the Best!</h2>
                 <p>Rate how well each player matched the goal.</p>
+                <p id="voting-timer" style="font-size: 1.5rem; color: #e94560; font-weight: bold;"></p>
                 <ul id="voting-list" style="list-style: none; padding: 0;"></ul>
                 <div id="voting-status" class="hidden" style="margin-top: 1rem; color: #4ecca3;">Waiting for others...</div>
                 <button id="btn-submit-votes" class="btn-primary" onclick="submitAllVotes()">Submit Votes</button>
@@ -421,6 +628,10 @@ async fn health_check() -> Html<&'static str> {
             <!-- Results Stage -->
             <div id="stage-results" class="hidden">
                 <h2>Final Results</h2>
+                <div id="outcome-display" class="goal-display hidden" style="margin-bottom: 1rem;">
+                    <h3 id="outcome-title"></h3>
+                    <p id="outcome-explanation" style="margin-bottom: 0;"></p>
+                </div>
                 <div id="podium-container"></div>
                 <ul id="final-results-list" style="list-style: none; padding: 0; margin-top: 2rem;"></ul>
                 <div class="actions">
@@ -435,9 +646,26 @@ async fn health_check() -> Html<&'static str> {
     </div>
 
     <script>
-        let currentRoom = null; // { room_id, room_code, player_id, nickname, isTV }
+        let currentRoom = null; // { room_id, room_code, player_id | spectator_id, session_token, nickname, isTV, isSpectator }
         let pollInterval = null;
         let timerInterval = null;
+        let heartbeatInterval = null;
+        let socket = null;
+        let eventSource = null;
+        let roomState = null; // latest snapshot with pushed events applied
+        let serverClockOffset = 0; // server time minus local time, in seconds
+
+        // Mutating requests prove who we are with the secret session token
+        function authHeaders() {
+            return {
+                'Content-Type': 'application/json',
+                'Authorization': `Bearer ${currentRoom.session_token}`
+            };
+        }
+
+        function serverNow() {
+            return Math.floor(Date.now() / 1000) + serverClockOffset;
+        }
 
         function updateDebugInfo(data) {
             const debugEl = document.getElementById('debug-info');
@@ -479,6 +707,7 @@ async fn health_check() -> Html<&'static str> {
                 currentRoom = {
                     room_id: createData.room_id,
                     room_code: createData.room_code,
+                    session_token: createData.session_token,
                     isTV: true
                 };
 
@@ -506,14 +735,9 @@ async fn health_check() -> Html<&'static str> {
                 const data = await response.json();
                 
                 if (response.ok) {
-                    currentRoom = {
-                        room_id: data.room_id,
-                        room_code: code,
-                        player_id: data.player_id,
-                        nickname: nickname,
-                        isTV: false
-                    };
-                    enterLobby();
+                    enterAsPlayer(code, nickname, data);
+                } else if (response.status === 409 && await rejoin(code, { nickname })) {
+                    // Our own seat, left behind when we lost the connection
                 } else {
                     showResult(data.message || 'Room not found or full', true);
                 }
@@ -522,70 +746,372 @@ async fn health_check() -> Html<&'static str> {
             }
         }
 
+        function enterAsPlayer(code, nickname, data) {
+            currentRoom = {
+                room_id: data.room_id,
+                room_code: code,
+                player_id: data.player_id,
+                session_token: data.session_token,
+                nickname: nickname,
+                isTV: false
+            };
+            // Lets a reload resume this seat with the token
+            sessionStorage.setItem('seat', JSON.stringify(currentRoom));
+            enterLobby();
+        }
+
+        // Spectators can come in at any stage and see what the TV sees
+        async function watchRoom() {
+            const code = document.getElementById('join-code').value.trim().toUpperCase();
+            const nickname = document.getElementById('join-nickname').value.trim();
+
+            if (!code || !nickname) {
+                showResult('Please enter both code and nickname', true);
+                return;
+            }
+
+            try {
+                const response = await fetch(`/rooms/${code}/spectate`, {
+                    method: 'POST',
+                    headers: { 'Content-Type': 'application/json' },
+                    body: JSON.stringify({ nickname })
+                });
+                const data = await response.json();
+                if (!response.ok) {
+                    showResult(data.message || 'Room not found', true);
+                    return;
+                }
+                currentRoom = {
+                    room_id: data.room_id,
+                    room_code: code,
+                    spectator_id: data.spectator_id,
+                    session_token: data.session_token,
+                    nickname: nickname,
+                    isTV: false,
+                    isSpectator: true
+                };
+                enterLobby();
+                roomState = data.room;
+                renderRoom(roomState);
+            } catch (err) {
+                showResult('Network error', true);
+            }
+        }
+
+        // Rejoin an existing seat by resume token or (while disconnected) nickname
+        async function rejoin(code, credentials) {
+            try {
+                const response = await fetch(`/rooms/${code}/rejoin`, {
+                    method: 'POST',
+                    headers: { 'Content-Type': 'application/json' },
+                    body: JSON.stringify(credentials)
+                });
+                if (!response.ok) return false;
+                const data = await response.json();
+                const me = data.room.players.find(p => p.id === data.player_id);
+                enterAsPlayer(code, me ? me.nickname : credentials.nickname, data);
+                roomState = data.room;
+                renderRoom(roomState);
+                return true;
+            } catch (err) {
+                return false;
+            }
+        }
+
+        async function resumeSeat() {
+            const seat = JSON.parse(sessionStorage.getItem('seat') || 'null');
+            if (!seat) return;
+            if (!await rejoin(seat.room_code, { resume_token: seat.session_token })) {
+                sessionStorage.removeItem('seat');
+            }
+        }
+
         function enterLobby() {
             document.getElementById('display-code').textContent = currentRoom.room_code;
             showView('lobby');
             
             if (currentRoom.isTV) {
-                document.getElementById('btn-start-game').classList.remove('hidden');
                 document.getElementById('lobby-status').textContent = "Waiting for players to join...";
+            } else if (currentRoom.isSpectator) {
+                document.getElementById('lobby-status').textContent = "Watching. The game starts when the host is ready...";
             } else {
-                document.getElementById('btn-start-game').classList.add('hidden');
-                document.getElementById('lobby-status').textContent = "Waiting for TV to start game...";
+                document.getElementById('lobby-status').textContent = "Waiting for the host to start the game...";
             }
 
+            connectSocket();
+            // Re-render every second so countdowns keep ticking between events
+            timerInterval = setInterval(() => roomState && renderRoom(roomState), 1000);
+            // Without an open socket, tell the server we're still here
+            heartbeatInterval = setInterval(() => {
+                if (socket && socket.readyState === WebSocket.OPEN) return;
+                fetch(`/rooms/${currentRoom.room_id}/heartbeat`, { method: 'POST', headers: authHeaders() })
+                    .catch(() => {});
+            }, 5000);
+        }
+
+        // Room updates are pushed over a WebSocket. Where proxies block the
+        // upgrade we use Server-Sent Events instead, and poll only as a last resort.
+        function connectSocket() {
+            const proto = location.protocol === 'https:' ? 'wss' : 'ws';
+            let opened = false;
+            // The token keeps us marked connected while the socket is open
+            const token = encodeURIComponent(currentRoom.session_token);
+            socket = new WebSocket(`${proto}://${location.host}/rooms/${currentRoom.room_id}/ws?token=${token}`);
+            socket.onopen = () => {
+                opened = true;
+                stopPolling();
+            };
+            socket.onmessage = (msg) => handlePush(JSON.parse(msg.data));
+            socket.onclose = () => {
+                socket = null;
+                if (!currentRoom || currentRoom.closed) return;
+                if (!opened) {
+                    connectEventStream();
+                    return;
+                }
+                startPolling();
+                setTimeout(() => { if (currentRoom && !socket) connectSocket(); }, 3000);
+            };
+        }
+
+        function connectEventStream() {
+            // EventSource reconnects by itself and resumes from the last event ID
+            eventSource = new EventSource(`/rooms/${currentRoom.room_id}/events`);
+            eventSource.onopen = stopPolling;
+            eventSource.onmessage = (msg) => handlePush(JSON.parse(msg.data));
+            eventSource.onerror = () => {
+                if (!currentRoom || currentRoom.closed) return;
+                if (eventSource.readyState === EventSource.CLOSED) eventSource = null;
+                startPolling();
+            };
+        }
+
+        function startPolling() {
+            if (pollInterval) return;
             updateGameState();
             pollInterval = setInterval(updateGameState, 1000);
         }
 
+        function stopPolling() {
+            clearInterval(pollInterval);
+            pollInterval = null;
+        }
+
+        function handlePush(msg) {
+            if (msg.type === 'snapshot') {
+                roomState = msg.room;
+                serverClockOffset = roomState.server_time - Math.floor(Date.now() / 1000);
+            } else if (msg.type === 'room_expired') {
+                roomClosed('Room closed after going idle');
+                return;
+            } else if (msg.type === 'room_closed') {
+                roomClosed();
+                return;
+            } else if (roomState) {
+                applyEvent(roomState, msg);
+            }
+            if (roomState) renderRoom(roomState);
+        }
+
+        function requestSnapshot() {
+            if (socket && socket.readyState === WebSocket.OPEN) {
+                socket.send('snapshot');
+            } else {
+                updateGameState();
+            }
+        }
+
+        function applyEvent(data, ev) {
+            if (ev.type === 'player_joined') {
+                data.players.push({
+                    id: ev.player_id, nickname: ev.nickname, avatar_id: ev.avatar_id,
+                    connected: true, starting_object: null
+                });
+                // A late joiner's starting object only comes with a snapshot
+                if (data.game) requestSnapshot();
+            } else if (ev.type === 'player_left') {
+                data.players = data.players.filter(p => p.id !== ev.player_id);
+            } else if (ev.type === 'spectator_joined') {
+                data.spectators.push({ id: ev.spectator_id, nickname: ev.nickname });
+            } else if (ev.type === 'spectator_left') {
+                data.spectators = data.spectators.filter(s => s.id !== ev.spectator_id);
+            } else if (ev.type === 'settings_changed') {
+                data.settings = ev.settings;
+            } else if (ev.type === 'host_changed') {
+                data.host = ev.host;
+            } else if (ev.type === 'connection_changed' && ev.participant.type === 'player') {
+                const player = data.players.find(p => p.id === ev.participant.player_id);
+                if (player) player.connected = ev.connected;
+            } else if (ev.type === 'game_started') {
+                // Goal, starting objects and images only come with a snapshot
+                requestSnapshot();
+            }
+            data.player_count = data.players.length;
+
+            const game = data.game;
+            if (!game) return;
+            if (ev.type === 'stage_changed') {
+                game.stage = ev.stage;
+                game.stage_deadline = ev.deadline;
+                // Scores are computed by the server
+                if (ev.stage === 'Results') requestSnapshot();
+            } else if (ev.type === 'player_ready') {
+                game.reveal_acknowledged.push(ev.player_id);
+            } else if (ev.type === 'turn_started') {
+                game.current_turn_player_id = ev.player_id;
+                game.current_round = ev.round;
+                game.current_options = ev.options;
+                game.turn_deadline = ev.deadline;
+            } else if (ev.type === 'action_applied') {
+                const action = ev.action;
+                game.player_current_objects[action.player_id] = action.resulting_object;
+                game.current_image_id = action.resulting_image;
+                game.current_image_url = data.image_base_url
+                    ? `${data.image_base_url}/${action.resulting_image}` : null;
+            } else if (ev.type === 'vote_received') {
+                game.players_who_voted.push(ev.voter_id);
+            } else if (ev.type === 'audience_vote_received') {
+                // Spectators may vote again, replacing their earlier votes
+                if (!game.audience_voters.includes(ev.spectator_id)) game.audience_voters.push(ev.spectator_id);
+            }
+        }
+
+        function roomClosed(message = 'Room closed') {
+            if (currentRoom.closed) return;
+            currentRoom.closed = true;
+            stopPolling();
+            showResult(message, true);
+            setTimeout(quitRoom, 2000);
+        }
+
         async function updateGameState() {
             if (!currentRoom) return;
             try {
                 const res = await fetch(`/rooms/${currentRoom.room_id}`);
                 if (!res.ok) {
-                    if (res.status === 404) {
-                        showResult('Room closed', true);
-                        setTimeout(quitRoom, 2000);
-                    }
+                    if (res.status === 404) roomClosed();
                     return;
                 }
-                const data = await res.json();
-                
-                // Update Player List
-                const list = document.getElementById('player-list');
-                list.innerHTML = data.players.map((p, index) => `
-                    <li>
-                        <span>${p.nickname}</span>
-                        <span style="color: ${p.connected ? '#4ecca3' : '#e94560'}">
-                            ${p.connected ? '●' : '○'}
-                        </span>
-                    </li>
-                `).join('');
-
-                // Enable Start Button for TV if enough players
-                if (currentRoom.isTV) {
-                    const startBtn = document.getElementById('btn-start-game');
-                    startBtn.disabled = data.players.length < 2;
-                }
-
-                // Game State Handling
-                if (data.state === 'InGame' && data.game) {
-                    showView('game');
-                    updateGameView(data);
-                }
-                
-                updateDebugInfo(data);
+                roomState = await res.json();
+                serverClockOffset = roomState.server_time - Math.floor(Date.now() / 1000);
+                renderRoom(roomState);
             } catch (err) {
                 console.error('Polling error', err);
             }
         }
 
+        function renderRoom(data) {
+            // Update Player List
+            const list = document.getElementById('player-list');
+            list.innerHTML = data.players.map((p, index) => `
+                <li>
+                    <span>${p.nickname}</span>
+                    <span style="color: ${p.connected ? '#4ecca3' : '#e94560'}">
+                        ${p.connected ? '●' : '○'}
+                    </span>
+                </li>
+            `).join('');
+            const watching = data.spectators.map(s => s.nickname);
+            document.getElementById('spectator-list').textContent =
+                watching.length ? `Watching: ${watching.join(', ')}` : '';
+
+            const host = isHost(data);
+            renderSettings(data.settings, host);
+
+            // Enable Start Button for the host if the player count suits the settings
+            const startBtn = document.getElementById('btn-start-game');
+            startBtn.classList.toggle('hidden', !host);
+            if (host) {
+                startBtn.disabled = data.players.length < data.settings.min_players
+                    || data.players.length > data.settings.max_players;
+            }
+
+            // Game State Handling
+            if (data.state === 'InGame' && data.game) {
+                showView('game');
+                updateGameView(data);
+            }
+            
+            updateDebugInfo(data);
+        }
+
+        // Whether this client holds host privileges (they move if the host leaves)
+        function isHost(data) {
+            if (!data.host) return currentRoom.isTV;
+            return currentRoom.isTV
+                ? data.host.type === 'display'
+                : data.host.type === 'player' && data.host.player_id === currentRoom.player_id;
+        }
+
+        function renderSettings(settings, host) {
+            document.getElementById('settings-summary').textContent =
+                `${settings.rounds} rounds · ${settings.turn_seconds}s turns · ` +
+                `${settings.min_players}-${settings.max_players} players · ` +
+                `decks: ${settings.decks.join(', ')}`;
+
+            const form = document.getElementById('settings-form');
+            form.classList.toggle('hidden', !host);
+            // Fill the form once; after that the host's edits win until saved
+            if (!host || form.dataset.filled) return;
+            form.dataset.filled = 'true';
+            document.getElementById('setting-rounds').value = settings.rounds;
+            document.getElementById('setting-turn-seconds').value = settings.turn_seconds;
+            document.getElementById('setting-voting-seconds').value = settings.voting_seconds;
+            document.getElementById('setting-options').value = settings.options_per_turn;
+            document.getElementById('setting-disconnect-policy').value = settings.disconnect_policy;
+            document.getElementById('setting-late-join').value = String(settings.allow_late_join);
+            loadDecks(settings.decks);
+        }
+
+        async function loadDecks(selected) {
+            const res = await fetch('/decks');
+            if (!res.ok) return;
+            const select = document.getElementById('setting-decks');
+            select.innerHTML = '';
+            (await res.json()).forEach(deck => {
+                const option = document.createElement('option');
+                option.value = deck.id;
+                option.textContent = deck.name;
+                option.selected = selected.includes(deck.id);
+                select.appendChild(option);
+            });
+        }
+
+        async function saveSettings() {
+            if (!currentRoom || !roomState) return;
+            const value = (id) => parseInt(document.getElementById(id).value);
+            const settings = {
+                ...roomState.settings,
+                rounds: value('setting-rounds'),
+                turn_seconds: value('setting-turn-seconds'),
+                voting_seconds: value('setting-voting-seconds'),
+                options_per_turn: value('setting-options'),
+                disconnect_policy: document.getElementById('setting-disconnect-policy').value,
+                allow_late_join: document.getElementById('setting-late-join').value === 'true',
+                decks: Array.from(document.getElementById('setting-decks').selectedOptions, o => o.value)
+            };
+            const res = await fetch(`/rooms/${currentRoom.room_id}/settings`, {
+                method: 'PUT',
+                headers: authHeaders(),
+                body: JSON.stringify(settings)
+            });
+            if (res.ok) {
+                showResult('Settings saved');
+            } else {
+                const err = await res.json();
+                showResult(err.message || 'Failed to save settings', true);
+            }
+        }
+
         function updateGameView(data) {
             const game = data.game;
-            const isTV = currentRoom.isTV;
+            // Spectators see what the TV shows
+            const isTV = currentRoom.isTV || currentRoom.isSpectator;
 
             // Always update goal
             document.getElementById('display-goal').textContent = game.communal_goal;
+            setImage('display-goal-image', isTV ? game.goal_image_url : null);
+            setImage('tv-current-image', game.current_image_url);
 
             // Hide all stages first
             ['reveal', 'turn', 'voting', 'results'].forEach(s => {
@@ -600,15 +1126,12 @@ async fn health_check() -> Html<&'static str> {
                     document.getElementById('tv-reveal-info').classList.remove('hidden');
                     document.getElementById('player-reveal-info').classList.add('hidden');
 
-                    // Auto-advance logic
-                    if (game.stage_start_time) {
-                        const elapsed = Math.floor(Date.now() / 1000) - game.stage_start_time;
-                        const remaining = Math.max(0, 10 - elapsed);
-                        document.getElementById('tv-reveal-timer').textContent = `Starting in ${remaining}...`;
-                        
-                        if (remaining === 0) {
-                            nextStage();
-                        }
+                    // The server ends the reveal on its own; just show the countdown
+                    if (game.stage_deadline) {
+                        const remaining = Math.max(0, game.stage_deadline - serverNow());
+                        const ready = game.reveal_acknowledged ? game.reveal_acknowledged.length : 0;
+                        document.getElementById('tv-reveal-timer').textContent =
+                            `Starting in ${remaining}... (${ready}/${data.players.length} ready)`;
                     }
                 } else {
                     document.getElementById('tv-reveal-info').classList.add('hidden');
@@ -617,16 +1140,22 @@ async fn health_check() -> Html<&'static str> {
                     const me = data.players.find(p => p.id === currentRoom.player_id);
                     const myObj = me ? me.starting_object : null;
                     document.getElementById('display-starting-object').textContent = myObj || 'Loading...';
+                    
+                    const isReady = game.reveal_acknowledged && game.reveal_acknowledged.includes(currentRoom.player_id);
+                    document.getElementById('btn-ready').classList.toggle('hidden', isReady);
+                    document.getElementById('ready-status').classList.toggle('hidden', !isReady);
                 }
             } else if (game.stage === 'PlayerTurn') {
                 document.getElementById('stage-turn').classList.remove('hidden');
                 document.getElementById('turn-status').textContent = `Round ${game.current_round + 1}`;
                 
-                // Timer logic
-                if (game.turn_start_time) {
-                    const elapsed = Math.floor(Date.now() / 1000) - game.turn_start_time;
-                    const remaining = Math.max(0, 10 - elapsed);
+                // Timer logic (the server skips the turn when the deadline passes)
+                if (game.turn_deadline) {
+                    const remaining = Math.max(0, game.turn_deadline - serverNow());
                     document.getElementById('turn-timer').textContent = remaining;
+                } else {
+                    // Held until the disconnected player returns
+                    document.getElementById('turn-timer').textContent = '⏸';
                 }
 
                 const currentPlayerId = game.current_turn_player_id;
@@ -656,7 +1185,9 @@ async fn health_check() -> Html<&'static str> {
                         if (optionsDiv.innerHTML === '' || optionsDiv.dataset.turn !== turnKey) {
                             optionsDiv.dataset.turn = turnKey;
                             optionsDiv.innerHTML = game.current_options.map((opt, idx) => `
-                                <button class="option-btn" onclick="submitAction(${idx})">${opt}</button>
+                                <button class="option-btn" onclick="submitAction(${idx})">
+                                    <span class="option-category">${opt.category}</span>${opt.text}
+                                </button>
                             `).join('');
                         }
                     } else {
@@ -667,20 +1198,38 @@ async fn health_check() -> Html<&'static str> {
                 }
             } else if (game.stage === 'Voting') {
                 document.getElementById('stage-voting').classList.remove('hidden');
+                // The server shows the results when the deadline passes
+                if (game.stage_deadline) {
+                    const remaining = Math.max(0, game.stage_deadline - serverNow());
+                    document.getElementById('voting-timer').textContent = `${remaining}s left`;
+                }
                 renderVotingList(data);
             } else if (game.stage === 'Results') {
                 document.getElementById('stage-results').classList.remove('hidden');
+                renderOutcome(game.evaluation);
                 renderResults(data);
             }
         }
 
+        function setImage(id, url) {
+            const img = document.getElementById(id);
+            if (!url) {
+                img.classList.add('hidden');
+                return;
+            }
+            if (img.getAttribute('src') !== url) img.setAttribute('src', url);
+            img.classList.remove('hidden');
+        }
+
         let hasVoted = false;
         let resultsShown = false;
 
         function renderVotingList(data) {
             const game = data.game;
-            const myId = currentRoom.player_id;
             const isTV = currentRoom.isTV;
+            // Spectators rate every player; their votes are scored separately
+            const myId = currentRoom.isSpectator ? currentRoom.spectator_id : currentRoom.player_id;
+            const voters = (currentRoom.isSpectator ? game.audience_voters : game.players_who_voted) || [];
 
             // TV just shows status
             if (isTV) {
@@ -688,15 +1237,17 @@ async fn health_check() -> Html<&'static str> {
                 document.getElementById('btn-submit-votes').classList.add('hidden');
                 
                 // Show who has voted
-                const votedCount = game.players_who_voted ? game.players_who_voted.length : 0;
-                const totalPlayers = data.players.length;
-                document.getElementById('voting-status').textContent = `${votedCount}/${totalPlayers} players have voted.`;
+                // Only connected players are waited for
+                const voted = game.players_who_voted || [];
+                const eligible = data.players.filter(p => p.connected);
+                const votedCount = eligible.filter(p => voted.includes(p.id)).length;
+                document.getElementById('voting-status').textContent = `${votedCount}/${eligible.length} players have voted.`;
                 document.getElementById('voting-status').classList.remove('hidden');
                 return;
             }
 
             // Check if I have already voted
-            if (game.players_who_voted && game.players_who_voted.includes(myId)) {
+            if (voters.includes(myId)) {
                 document.getElementById('voting-list').innerHTML = '';
                 document.getElementById('btn-submit-votes').classList.add('hidden');
                 document.getElementById('voting-status').classList.remove('hidden');
@@ -769,13 +1320,11 @@ async fn health_check() -> Html<&'static str> {
             }
 
             try {
-                const res = await fetch(`/rooms/${currentRoom.room_id}/votes`, {
+                const endpoint = currentRoom.isSpectator ? 'audience-votes' : 'votes';
+                const res = await fetch(`/rooms/${currentRoom.room_id}/${endpoint}`, {
                     method: 'POST',
-                    headers: { 'Content-Type': 'application/json' },
-                    body: JSON.stringify({
-                        voter_id: currentRoom.player_id,
-                        votes: votes
-                    })
+                    headers: authHeaders(),
+                    body: JSON.stringify({ votes: votes })
                 });
 
                 if (res.ok) {
@@ -793,19 +1342,33 @@ async fn health_check() -> Html<&'static str> {
             }
         }
 
+        function renderOutcome(evaluation) {
+            const box = document.getElementById('outcome-display');
+            // Arrives with the snapshot requested when Results begins
+            if (!evaluation) return;
+            const titles = { Success: 'You made it!', Close: 'So close!', Fail: 'Not this time...' };
+            document.getElementById('outcome-title').textContent = titles[evaluation.outcome] || evaluation.outcome;
+            document.getElementById('outcome-explanation').textContent = evaluation.explanation;
+            box.classList.remove('hidden');
+        }
+
         function renderResults(data) {
             if (resultsShown) return;
             resultsShown = true;
 
             const scores = data.game.scores || {};
+            const audienceScores = data.game.audience_scores || {};
             const players = data.players.map(p => ({
                 ...p,
-                score: scores[p.id] || 0,
+                score: scores[p.id] ?? null,
+                audienceScore: audienceScores[p.id] ?? null,
                 finalObj: data.game.player_current_objects[p.id]
             }));
+            const formatScore = (score) => score === null ? 'No votes' : `${score.toFixed(1)} pts`;
+            const audienceLine = (p) => p.audienceScore === null ? '' : ` · audience ${p.audienceScore.toFixed(1)}`;
 
             // Sort by score descending
-            players.sort((a, b) => b.score - a.score);
+            players.sort((a, b) => (b.score ?? -1) - (a.score ?? -1));
 
             const container = document.getElementById('podium-container');
             container.innerHTML = ''; // Clear previous
@@ -829,7 +1392,7 @@ async fn health_check() -> Html<&'static str> {
                     el.innerHTML = `
                         <div class="rank">#${rank}</div>
                         <div class="name">${p.nickname}</div>
-                        <div class="score">${p.score.toFixed(1)} pts</div>
+                        <div class="score">${formatScore(p.score)}${audienceLine(p)}</div>
                         <div class="obj">${p.finalObj}</div>
                     `;
                     container.appendChild(el);
@@ -848,7 +1411,7 @@ async fn health_check() -> Html<&'static str> {
                 list.innerHTML = players.map((p, i) => `
                     <li style="padding: 0.5rem; border-bottom: 1px solid #333; display: flex; justify-content: space-between;">
                         <span>${i+1}. ${p.nickname} (${p.finalObj})</span>
-                        <span>${p.score.toFixed(1)}</span>
+                        <span>${formatScore(p.score)}${audienceLine(p)}</span>
                     </li>
                 `).join('');
             }, delay + 1000);
@@ -856,12 +1419,17 @@ async fn health_check() -> Html<&'static str> {
 
         async function startGame() {
             if (!currentRoom) return;
-            await fetch(`/rooms/${currentRoom.room_id}/start`, { method: 'POST' });
+            await fetch(`/rooms/${currentRoom.room_id}/start`, { method: 'POST', headers: authHeaders() });
+        }
+
+        async function acknowledgeReveal() {
+            if (!currentRoom) return;
+            await fetch(`/rooms/${currentRoom.room_id}/ready`, { method: 'POST', headers: authHeaders() });
         }
 
         async function nextStage() {
             if (!currentRoom) return;
-            await fetch(`/rooms/${currentRoom.room_id}/next`, { method: 'POST' });
+            await fetch(`/rooms/${currentRoom.room_id}/next`, { method: 'POST', headers: authHeaders() });
         }
 
         async function submitAction(index) {
@@ -869,11 +1437,8 @@ async fn health_check() -> Html<&'static str> {
             try {
                 await fetch(`/rooms/${currentRoom.room_id}/action`, {
                     method: 'POST',
-                    headers: { 'Content-Type': 'application/json' },
-                    body: JSON.stringify({ 
-                        player_id: currentRoom.player_id,
-                        option_index: index 
-                    })
+                    headers: authHeaders(),
+                    body: JSON.stringify({ option_index: index })
                 });
                 // Clear options to prevent double click
                 document.getElementById('turn-options').innerHTML = '<p>Submitted!</p>';
@@ -884,20 +1449,27 @@ async fn health_check() -> Html<&'static str> {
 
 
         async function quitRoom() {
-            if (currentRoom && !currentRoom.isTV) {
+            if (currentRoom && !currentRoom.closed) {
+                // The TV hosts the room, so quitting there closes it for everyone
+                const action = currentRoom.isTV ? 'close' : 'leave';
                 try {
-                    await fetch(`/rooms/${currentRoom.room_id}/leave`, {
-                        method: 'POST',
-                        headers: { 'Content-Type': 'application/json' },
-                        body: JSON.stringify({ player_id: currentRoom.player_id })
-                    });
+                    await fetch(`/rooms/${currentRoom.room_id}/${action}`, { method: 'POST', headers: authHeaders() });
                 } catch (err) {}
             }
             
-            clearInterval(pollInterval);
+            stopPolling();
+            clearInterval(timerInterval);
+            clearInterval(heartbeatInterval);
             currentRoom = null;
+            roomState = null;
+            if (socket) socket.close();
+            if (eventSource) eventSource.close();
+            eventSource = null;
+            sessionStorage.removeItem('seat');
             showView('selection');
         }
+
+        resumeSeat();
     </script>
 </body>
 </html>
@@ -906,10 +1478,26 @@ async fn health_check() -> Html<&'static str> {
 
 // --- Request/Response DTOs ---
 
+#[derive(Debug, Serialize, Deserialize)]
+struct DeckInfo {
+    /// Id to list in the room's `decks` setting.
+    id: String,
+    name: String,
+    version: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CreateRoomQuery {
+    /// Seed for reproducing a game (testing and bug reports).
+    seed: Option<u64>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct CreateRoomResponse {
     room_code: String,
     room_id: String,
+    /// Secret for the display's (host's) requests; keep it private.
+    session_token: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -922,22 +1510,44 @@ struct JoinRoomRequest {
 struct JoinRoomResponse {
     player_id: String,
     room_id: String,
+    /// Secret for this player's requests; keep it private.
+    session_token: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct LeaveRoomRequest {
-    player_id: String,
+struct SpectateRoomRequest {
+    nickname: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SpectateRoomResponse {
+    spectator_id: String,
+    room_id: String,
+    /// Secret for this spectator's requests; keep it private.
+    session_token: String,
+    /// The room as it is now, since spectators may arrive mid-game.
+    room: RoomStateResponse,
 }
 
+/// Either field identifies the player; the token wins if both are given.
 #[derive(Debug, Serialize, Deserialize)]
 struct RejoinRoomRequest {
-    nickname: String,
+    /// Session token from the player's last join or rejoin.
+    #[serde(default)]
+    resume_token: Option<String>,
+    /// Accepted only while that player is disconnected.
+    #[serde(default)]
+    nickname: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct RejoinRoomResponse {
     player_id: String,
     room_id: String,
+    /// New secret for this player's requests; earlier ones stop working.
+    session_token: String,
+    /// The room as it is now, to restore the player's view.
+    room: RoomStateResponse,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -947,7 +1557,15 @@ struct RoomStateResponse {
     state: String,
     player_count: usize,
     players: Vec<PlayerInfo>,
+    spectators: Vec<SpectatorInfo>,
     game: Option<GameInfo>,
+    settings: GameSettings,
+    /// Who may change settings, start, advance and close the game.
+    host: Participant,
+    /// Server clock (Unix seconds) so clients can align countdowns.
+    server_time: u64,
+    /// URL prefix for image IDs in pushed events, if images are served.
+    image_base_url: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -958,24 +1576,40 @@ struct GameInfo {
     player_current_objects: std::collections::HashMap<String, String>,
     current_image_id: String,
     goal_image_id: String,
+    current_image_url: Option<String>,
+    goal_image_url: Option<String>,
     current_turn_player_id: Option<String>,
-    current_options: Vec<String>,
+    current_options: Vec<ModifierOption>,
     turn_start_time: Option<u64>,
+    turn_deadline: Option<u64>,
+    turn_seconds: u64,
     stage_start_time: u64,
+    stage_deadline: Option<u64>,
+    reveal_acknowledged: Vec<String>,
     current_round: u32,
+    /// Average stars per player; players nobody rated are missing.
     scores: std::collections::HashMap<String, f32>,
     players_who_voted: Vec<String>,
+    /// Connected players, whose votes end voting early once all are in.
+    eligible_voters: Vec<String>,
+    /// Average stars per player from spectators, reported apart from `scores`.
+    audience_scores: std::collections::HashMap<String, f32>,
+    /// Spectators who have voted.
+    audience_voters: Vec<String>,
+    /// How close the group got to the goal (Results stage only).
+    evaluation: Option<GameEvaluation>,
+    /// Seed the game was drawn from; create a room with `?seed=` to replay
+    /// the same draws. A string, since it may not fit a JSON number.
+    seed: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct SubmitActionRequest {
-    player_id: String,
     option_index: Option<usize>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct SubmitVotesRequest {
-    voter_id: String,
     votes: std::collections::HashMap<String, u8>,
 }
 
@@ -988,20 +1622,33 @@ struct PlayerInfo {
     starting_object: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct SpectatorInfo {
+    id: String,
+    nickname: String,
+}
+
 // --- Handlers ---
 
-/// POST /rooms - Create a new room.
+/// POST /rooms - Create a new room, optionally with `?seed=` fixing its
+/// code and every draw of its game.
 async fn create_room(
     State(state): State<AppState>,
+    Query(query): Query<CreateRoomQuery>,
 ) -> Result<Json<CreateRoomResponse>, AppError> {
     let mut manager = state.room_manager.write().await;
-    let (room_id, code) = manager.create_room();
+    let (room_id, code) = match query.seed {
+        Some(seed) => manager.create_seeded_room(seed),
+        None => manager.create_room(),
+    };
+    let token = manager.issue_session(room_id, Participant::Display)?;
     
     tracing::info!("Created room {} with code {}", room_id, code);
     
     Ok(Json(CreateRoomResponse {
         room_code: code,
         room_id: room_id.to_string(),
+        session_token: token.as_str().to_string(),
     }))
 }
 
@@ -1017,6 +1664,8 @@ async fn join_room(
     let (room_id, player_id) = manager
         .join_room(&code, req.nickname.clone(), avatar)
         .map_err(AppError::from)?;
+    let token = manager.issue_session(room_id, Participant::Player { player_id })?;
+    state.publish(&mut manager);
     
     tracing::info!(
         "Player {} ({}) joined room {} (code: {})",
@@ -1029,73 +1678,194 @@ async fn join_room(
     Ok(Json(JoinRoomResponse {
         player_id: player_id.to_string(),
         room_id: room_id.to_string(),
+        session_token: token.as_str().to_string(),
+    }))
+}
+
+/// POST /rooms/:code/spectate - Watch a room by code, at any stage.
+async fn spectate_room(
+    State(state): State<AppState>,
+    Path(code): Path<String>,
+    Json(req): Json<SpectateRoomRequest>,
+) -> Result<Json<SpectateRoomResponse>, AppError> {
+    let mut manager = state.room_manager.write().await;
+    
+    let (room_id, spectator_id) = manager.join_as_spectator(&code, req.nickname.clone())?;
+    let token = manager.issue_session(room_id, Participant::Spectator { spectator_id })?;
+    state.publish(&mut manager);
+    let room = room_snapshot(&state, &manager, &room_id)?;
+    
+    tracing::info!(
+        "Spectator {} ({}) is watching room {} (code: {})",
+        req.nickname,
+        spectator_id,
+        room_id,
+        code
+    );
+    
+    Ok(Json(SpectateRoomResponse {
+        spectator_id: spectator_id.to_string(),
+        room_id: room_id.to_string(),
+        session_token: token.as_str().to_string(),
+        room,
     }))
 }
 
 /// POST /rooms/:room_id/leave - Leave a room.
+///
+/// The display keeps its place and is only marked disconnected, handing host
+/// privileges to a player until it reconnects.
 async fn leave_room(
     State(state): State<AppState>,
     Path(room_id_str): Path<String>,
-    Json(req): Json<LeaveRoomRequest>,
+    Bearer(token): Bearer,
 ) -> Result<StatusCode, AppError> {
     let mut manager = state.room_manager.write().await;
     
     let room_id = RoomId::from_string(&room_id_str)
         .map_err(|_| AppError::InvalidRoomId)?;
-    let player_id = PlayerId::from_string(&req.player_id)
-        .map_err(|_| AppError::InvalidPlayerId)?;
     
-    manager
-        .leave_room(room_id, player_id)
-        .map_err(AppError::from)?;
+    match manager.authenticate(&room_id, &token)?.participant {
+        Participant::Player { player_id } => {
+            manager.leave_room(room_id, player_id)?;
+            tracing::info!("Player {} left room {}", player_id, room_id);
+        }
+        Participant::Display => {
+            manager.disconnect(room_id, Participant::Display)?;
+            tracing::info!("Display left room {}", room_id);
+        }
+        Participant::Spectator { spectator_id } => {
+            manager.leave_as_spectator(room_id, spectator_id)?;
+            tracing::info!("Spectator {} left room {}", spectator_id, room_id);
+        }
+    }
+    state.publish(&mut manager);
     
-    tracing::info!("Player {} left room {}", req.player_id, room_id);
+    Ok(StatusCode::OK)
+}
+
+/// POST /rooms/:room_id/reconnect - Mark the caller connected again.
+async fn reconnect(
+    State(state): State<AppState>,
+    Path(room_id_str): Path<String>,
+    Bearer(token): Bearer,
+) -> Result<StatusCode, AppError> {
+    let mut manager = state.room_manager.write().await;
+    let room_id = RoomId::from_string(&room_id_str)
+        .map_err(|_| AppError::InvalidRoomId)?;
+    let participant = manager.authenticate(&room_id, &token)?.participant;
+    
+    manager.reconnect(room_id, participant)?;
+    state.publish(&mut manager);
+    
+    tracing::info!("{:?} reconnected to room {}", participant, room_id);
     
     Ok(StatusCode::OK)
 }
 
-/// POST /rooms/:code/rejoin - Rejoin a room by nickname.
+/// POST /rooms/:room_id/heartbeat - Tell the server the caller is still there.
+async fn heartbeat(
+    State(state): State<AppState>,
+    Path(room_id_str): Path<String>,
+    Bearer(token): Bearer,
+) -> Result<StatusCode, AppError> {
+    let room_id = RoomId::from_string(&room_id_str)
+        .map_err(|_| AppError::InvalidRoomId)?;
+    let participant = state.room_manager.read().await
+        .authenticate(&room_id, &token)?
+        .participant;
+    
+    presence::touch(&state, room_id, participant).await;
+    
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /rooms/:room_id/close - Close the room for everyone (host only).
+async fn close_room(
+    State(state): State<AppState>,
+    Path(room_id_str): Path<String>,
+    Bearer(token): Bearer,
+) -> Result<StatusCode, AppError> {
+    let mut manager = state.room_manager.write().await;
+    let room_id = RoomId::from_string(&room_id_str)
+        .map_err(|_| AppError::InvalidRoomId)?;
+    manager.authorize_host(&room_id, &token)?;
+    
+    manager.close_room(room_id)?;
+    state.publish(&mut manager);
+    
+    tracing::info!("Host closed room {}", room_id);
+    
+    Ok(StatusCode::OK)
+}
+
+/// POST /rooms/:code/rejoin - Rejoin a room as an existing player.
 async fn rejoin_room(
     State(state): State<AppState>,
     Path(code): Path<String>,
     Json(req): Json<RejoinRoomRequest>,
 ) -> Result<Json<RejoinRoomResponse>, AppError> {
-    let mut manager = state.room_manager.write().await;
+    let credentials = match (req.resume_token, req.nickname) {
+        (Some(token), _) => Rejoin::ResumeToken(SessionToken::new(token)),
+        (None, Some(nickname)) => Rejoin::Nickname(nickname),
+        (None, None) => return Err(AppError::Join(JoinError::InvalidNickname)),
+    };
     
-    let (room_id, player_id) = manager
-        .rejoin_room(&code, &req.nickname)
-        .map_err(AppError::from)?;
+    let mut manager = state.room_manager.write().await;
+    let (room_id, player_id) = manager.rejoin_room(&code, credentials)?;
+    let token = manager.issue_session(room_id, Participant::Player { player_id })?;
+    state.publish(&mut manager);
+    let room = room_snapshot(&state, &manager, &room_id)?;
     
-    tracing::info!(
-        "Player {} rejoined room {} (code: {})",
-        req.nickname,
-        room_id,
-        code
-    );
+    tracing::info!("Player {} rejoined room {} (code: {})", player_id, room_id, code);
     
     Ok(Json(RejoinRoomResponse {
         player_id: player_id.to_string(),
         room_id: room_id.to_string(),
+        session_token: token.as_str().to_string(),
+        room,
     }))
 }
 
-/// POST /rooms/:room_id/start - Start the game (placeholder).
+/// PUT /rooms/:room_id/settings - Change the game settings while in the lobby.
+async fn update_settings(
+    State(state): State<AppState>,
+    Path(room_id_str): Path<String>,
+    Bearer(token): Bearer,
+    Json(settings): Json<GameSettings>,
+) -> Result<StatusCode, AppError> {
+    let mut manager = state.room_manager.write().await;
+    let room_id = RoomId::from_string(&room_id_str)
+        .map_err(|_| AppError::InvalidRoomId)?;
+    manager.authorize_host(&room_id, &token)?;
+    
+    manager.update_settings(&room_id, settings)?;
+    state.publish(&mut manager);
+    
+    Ok(StatusCode::OK)
+}
+
+/// POST /rooms/:room_id/start - Start the game (host only), drawing its goal
+/// and starting images and starting its stage timer.
 async fn start_game(
     State(state): State<AppState>,
     Path(room_id_str): Path<String>,
+    Bearer(token): Bearer,
 ) -> Result<StatusCode, AppError> {
     let manager = state.room_manager.read().await;
     let room_id = RoomId::from_string(&room_id_str)
         .map_err(|_| AppError::InvalidRoomId)?;
+    manager.authorize_host(&room_id, &token)?;
     
     let room = manager
         .get_room(&room_id)
         .ok_or(RoomError::RoomNotFound)?;
     
     let player_count = room.player_count();
+    let allowed = room.settings.player_range();
     
-    if !(2..=8).contains(&player_count) {
-        return Err(AppError::InvalidPlayerCount(player_count));
+    if !allowed.contains(&player_count) {
+        return Err(AppError::InvalidPlayerCount { count: player_count, allowed });
     }
     
     tracing::info!("Starting game in room {} with {} players", room_id, player_count);
@@ -1105,86 +1875,117 @@ async fn start_game(
     
     let mut manager = state.room_manager.write().await;
     manager.start_game(&room_id)?;
+    state.publish(&mut manager);
+    drop(manager);
+    
+    stage_timer::spawn(state.clone(), room_id);
+    
+    Ok(StatusCode::OK)
+}
+
+/// POST /rooms/:room_id/ready - Acknowledge the goal reveal.
+///
+/// The reveal ends early once every connected player is ready.
+async fn acknowledge_reveal(
+    State(state): State<AppState>,
+    Path(room_id_str): Path<String>,
+    Bearer(token): Bearer,
+) -> Result<StatusCode, AppError> {
+    let mut manager = state.room_manager.write().await;
+    let room_id = RoomId::from_string(&room_id_str)
+        .map_err(|_| AppError::InvalidRoomId)?;
+    let player_id = manager.authenticate_player(&room_id, &token)?;
+    
+    if manager.acknowledge_reveal(&room_id, player_id)? {
+        tracing::info!("All players ready in room {}, starting turns", room_id);
+    }
+    state.publish(&mut manager);
     
     Ok(StatusCode::OK)
 }
 
 /// POST /rooms/:room_id/next - Transition to the next game stage.
+///
+/// Stages advance on their own; this is a host override to skip ahead.
 async fn next_stage(
     State(state): State<AppState>,
     Path(room_id_str): Path<String>,
+    Bearer(token): Bearer,
 ) -> Result<StatusCode, AppError> {
     let mut manager = state.room_manager.write().await;
     let room_id = RoomId::from_string(&room_id_str)
         .map_err(|_| AppError::InvalidRoomId)?;
+    manager.authorize_host(&room_id, &token)?;
     
-    let room = manager
-        .get_room_mut(&room_id)
-        .ok_or(RoomError::RoomNotFound)?;
+    manager.next_stage(&room_id)?;
+    state.publish(&mut manager);
     
-    if let Some(game) = &mut room.game {
-        game.next_stage();
-        Ok(StatusCode::OK)
-    } else {
-        Err(RoomError::Internal("Game not started".to_string()).into())
-    }
+    Ok(StatusCode::OK)
 }
 
 /// POST /rooms/:room_id/action - Submit a player action.
 async fn submit_action(
     State(state): State<AppState>,
     Path(room_id_str): Path<String>,
+    Bearer(token): Bearer,
     Json(req): Json<SubmitActionRequest>,
 ) -> Result<StatusCode, AppError> {
     let mut manager = state.room_manager.write().await;
     let room_id = RoomId::from_string(&room_id_str)
         .map_err(|_| AppError::InvalidRoomId)?;
-    let player_id = PlayerId::from_string(&req.player_id)
-        .map_err(|_| AppError::InvalidPlayerId)?;
+    let player_id = manager.authenticate_player(&room_id, &token)?;
     
-    let room = manager
-        .get_room_mut(&room_id)
-        .ok_or(RoomError::RoomNotFound)?;
+    manager.submit_action(&room_id, player_id, req.option_index)?;
+    state.publish(&mut manager);
     
-    if let Some(game) = &mut room.game {
-        game.submit_action(player_id, req.option_index)
-            .map_err(|e| RoomError::Internal(e))?;
-        Ok(StatusCode::OK)
-    } else {
-        Err(RoomError::Internal("Game not started".to_string()).into())
-    }
+    Ok(StatusCode::OK)
 }
 
 /// POST /rooms/:room_id/votes - Submit votes.
 async fn submit_votes(
     State(state): State<AppState>,
     Path(room_id_str): Path<String>,
+    Bearer(token): Bearer,
     Json(req): Json<SubmitVotesRequest>,
 ) -> Result<StatusCode, AppError> {
     let mut manager = state.room_manager.write().await;
     let room_id = RoomId::from_string(&room_id_str)
         .map_err(|_| AppError::InvalidRoomId)?;
-    let voter_id = PlayerId::from_string(&req.voter_id)
-        .map_err(|_| AppError::InvalidPlayerId)?;
+    let voter_id = manager.authenticate_player(&room_id, &token)?;
     
-    let room = manager
-        .get_room_mut(&room_id)
-        .ok_or(RoomError::RoomNotFound)?;
+    manager.submit_votes(&room_id, voter_id, parse_votes(req)?)?;
+    state.publish(&mut manager);
+    
+    Ok(StatusCode::OK)
+}
+
+/// POST /rooms/:room_id/audience-votes - Submit a spectator's votes.
+async fn submit_audience_votes(
+    State(state): State<AppState>,
+    Path(room_id_str): Path<String>,
+    Bearer(token): Bearer,
+    Json(req): Json<SubmitVotesRequest>,
+) -> Result<StatusCode, AppError> {
+    let mut manager = state.room_manager.write().await;
+    let room_id = RoomId::from_string(&room_id_str)
+        .map_err(|_| AppError::InvalidRoomId)?;
+    let spectator_id = manager.authenticate_spectator(&room_id, &token)?;
     
-    if let Some(game) = &mut room.game {
-        let mut votes = std::collections::HashMap::new();
-        for (target_str, stars) in req.votes {
-            let target_id = PlayerId::from_string(&target_str)
-                .map_err(|_| AppError::InvalidPlayerId)?;
-            votes.insert(target_id, stars);
-        }
-
-        game.submit_votes(voter_id, votes)
-            .map_err(|e| RoomError::Internal(e))?;
-        Ok(StatusCode::OK)
-    } else {
-        Err(RoomError::Internal("Game not started".to_string()).into())
+    manager.submit_audience_votes(&room_id, spectator_id, parse_votes(req)?)?;
+    state.publish(&mut manager);
+    
+    Ok(StatusCode::OK)
+}
+
+/// Parse the player IDs votes are keyed by.
+fn parse_votes(req: SubmitVotesRequest) -> Result<std::collections::HashMap<PlayerId, u8>, AppError> {
+    let mut votes = std::collections::HashMap::new();
+    for (target_str, stars) in req.votes {
+        let target_id = PlayerId::from_string(&target_str)
+            .map_err(|_| AppError::InvalidPlayerId)?;
+        votes.insert(target_id, stars);
     }
+    Ok(votes)
 }
 
 /// GET /rooms/:room_id - Get current room state.
@@ -1196,8 +1997,46 @@ async fn get_room_state(
     let room_id = RoomId::from_string(&room_id_str)
         .map_err(|_| AppError::InvalidRoomId)?;
     
+    Ok(Json(room_snapshot(&state, &manager, &room_id)?))
+}
+
+/// GET /decks - List the decks hosts can pick for their room.
+async fn list_decks(State(state): State<AppState>) -> Json<Vec<DeckInfo>> {
+    let manager = state.room_manager.read().await;
+    let decks = manager.decks().iter()
+        .map(|deck| DeckInfo {
+            id: deck.id.clone(),
+            name: deck.name.clone(),
+            version: deck.version.clone(),
+        })
+        .collect();
+    Json(decks)
+}
+
+/// GET /rooms/:room_id/log - Get the room's event log (attach to bug reports).
+async fn get_room_log(
+    State(state): State<AppState>,
+    Path(room_id_str): Path<String>,
+) -> Result<Json<Vec<LoggedEvent>>, AppError> {
+    let manager = state.room_manager.read().await;
+    let room_id = RoomId::from_string(&room_id_str)
+        .map_err(|_| AppError::InvalidRoomId)?;
+    
+    let log = manager
+        .game_log(&room_id)
+        .ok_or(RoomError::RoomNotFound)?;
+    
+    Ok(Json(log.to_vec()))
+}
+
+/// Full state of a room as served to clients.
+fn room_snapshot(
+    state: &AppState,
+    manager: &RoomManager,
+    room_id: &RoomId,
+) -> Result<RoomStateResponse, AppError> {
     let room = manager
-        .get_room(&room_id)
+        .get_room(room_id)
         .ok_or(RoomError::RoomNotFound)?;
     
     let players: Vec<PlayerInfo> = room
@@ -1215,30 +2054,63 @@ async fn get_room_state(
         })
         .collect();
     
-    let game = room.game.as_ref().map(|g| GameInfo {
-        stage: format!("{:?}", g.stage),
-        communal_goal: g.communal_goal.clone(),
-        player_starting_objects: g.player_starting_objects.iter().map(|(k, v)| (k.to_string(), v.clone())).collect(),
-        player_current_objects: g.player_current_objects.iter().map(|(k, v)| (k.to_string(), v.clone())).collect(),
-        current_image_id: g.current_image.as_str().to_string(),
-        goal_image_id: g.goal_image.as_str().to_string(),
-        current_turn_player_id: g.current_player().map(|id| id.to_string()),
-        current_options: g.current_options.clone(),
-        turn_start_time: g.turn_start_time,
-        stage_start_time: g.stage_start_time,
-        current_round: g.current_round,
-        scores: g.calculate_scores().iter().map(|(k, v)| (k.to_string(), *v)).collect(),
-        players_who_voted: g.players_who_voted.iter().map(|id| id.to_string()).collect(),
+    let spectators: Vec<SpectatorInfo> = room
+        .spectators
+        .iter()
+        .map(|s| SpectatorInfo {
+            id: s.id.to_string(),
+            nickname: s.nickname.clone(),
+        })
+        .collect();
+    
+    let game = room.game.as_ref().map(|g| {
+        let scores = g.calculate_scores();
+        GameInfo {
+            stage: format!("{:?}", g.stage),
+            communal_goal: g.communal_goal.clone(),
+            player_starting_objects: g.player_starting_objects.iter().map(|(k, v)| (k.to_string(), v.clone())).collect(),
+            player_current_objects: g.player_current_objects.iter().map(|(k, v)| (k.to_string(), v.clone())).collect(),
+            current_image_id: g.current_image.as_str().to_string(),
+            goal_image_id: g.goal_image.as_str().to_string(),
+            current_image_url: image_url(state.image_base_url, &g.current_image),
+            goal_image_url: image_url(state.image_base_url, &g.goal_image),
+            current_turn_player_id: g.current_player().map(|id| id.to_string()),
+            current_options: g.current_options.clone(),
+            turn_start_time: g.turn_start_time,
+            turn_deadline: g.turn_deadline(),
+            turn_seconds: g.settings.turn_seconds,
+            stage_start_time: g.stage_start_time,
+            stage_deadline: g.stage_deadline(),
+            reveal_acknowledged: g.reveal_acknowledged.iter().map(|id| id.to_string()).collect(),
+            current_round: g.current_round,
+            scores: scores.players.iter().map(|(k, v)| (k.to_string(), *v)).collect(),
+            players_who_voted: g.players_who_voted.iter().map(|id| id.to_string()).collect(),
+            eligible_voters: g.eligible_voters().iter().map(|id| id.to_string()).collect(),
+            audience_scores: scores.audience.iter().map(|(k, v)| (k.to_string(), *v)).collect(),
+            audience_voters: g.audience_votes.keys().map(|id| id.to_string()).collect(),
+            evaluation: g.evaluation.clone(),
+            seed: g.rng.seed().to_string(),
+        }
     });
 
-    Ok(Json(RoomStateResponse {
+    Ok(RoomStateResponse {
         room_id: room_id.to_string(),
         room_code: room.code.clone(),
         state: format!("{:?}", room.state),
         player_count: room.player_count(),
         players,
+        spectators,
         game,
-    }))
+        settings: room.settings.clone(),
+        host: room.host,
+        server_time: stage_timer::unix_now(),
+        image_base_url: state.image_base_url.map(str::to_string),
+    })
+}
+
+/// Public URL of an image, if the configured backend serves files.
+fn image_url(base: Option<&str>, image: &ImageId) -> Option<String> {
+    base.map(|base| format!("{}/{}", base, image.as_str()))
 }
 
 // --- Error Handling ---
@@ -1247,7 +2119,7 @@ async fn get_room_state(
 enum AppError {
     Room(RoomError),
     Join(JoinError),
-    InvalidPlayerCount(usize),
+    InvalidPlayerCount { count: usize, allowed: std::ops::RangeInclusive<usize> },
     InvalidRoomId,
     InvalidPlayerId,
 }
@@ -1281,20 +2153,38 @@ impl IntoResponse for AppError {
             AppError::Room(RoomError::PlayerNotFoundSimple) | AppError::Room(RoomError::PlayerNotFound(_, _)) => {
                 (StatusCode::NOT_FOUND, "Player not found".to_string())
             }
+            AppError::Room(RoomError::SpectatorNotFound(_, _)) => {
+                (StatusCode::NOT_FOUND, "Spectator not found".to_string())
+            }
             AppError::Room(RoomError::GameAlreadyStarted) | AppError::Room(RoomError::AlreadyStarted(_)) => {
                 (StatusCode::CONFLICT, "Game already started".to_string())
             }
             AppError::Room(RoomError::NicknameTaken(_, _)) => {
                 (StatusCode::CONFLICT, "Nickname already taken".to_string())
             }
+            AppError::Room(RoomError::NicknameNotFound(nickname, _)) => {
+                (StatusCode::NOT_FOUND, format!("No player named '{}' in this room", nickname))
+            }
             AppError::Room(RoomError::InvalidCode(code)) => {
                 (StatusCode::NOT_FOUND, format!("Invalid room code: {}", code))
             }
+            AppError::Room(RoomError::ImageGeneration(msg)) => {
+                (StatusCode::BAD_GATEWAY, format!("Image generation failed: {}", msg))
+            }
             AppError::Room(RoomError::Internal(msg)) => {
                 (StatusCode::INTERNAL_SERVER_ERROR, msg)
             }
-            AppError::Room(RoomError::NotEnoughPlayers(_)) => {
-                (StatusCode::BAD_REQUEST, "Not enough players to start the game".to_string())
+            AppError::Room(RoomError::NotEnoughPlayers(_, min_players)) => {
+                (StatusCode::BAD_REQUEST, format!("Not enough players to start the game (need at least {})", min_players))
+            }
+            AppError::Room(RoomError::InvalidSettings(msg)) => {
+                (StatusCode::BAD_REQUEST, format!("Invalid settings: {}", msg))
+            }
+            AppError::Room(RoomError::Unauthorized) => {
+                (StatusCode::UNAUTHORIZED, "Missing or invalid session token".to_string())
+            }
+            AppError::Room(RoomError::Forbidden(msg)) => {
+                (StatusCode::FORBIDDEN, format!("Not allowed: {}", msg))
             }
             AppError::Join(JoinError::DuplicateNickname) => {
                 (StatusCode::CONFLICT, "Nickname already taken".to_string())
@@ -1311,8 +2201,11 @@ impl IntoResponse for AppError {
             AppError::Join(JoinError::InvalidNickname) => {
                 (StatusCode::BAD_REQUEST, "Invalid nickname".to_string())
             }
-            AppError::InvalidPlayerCount(count) => {
-                (StatusCode::BAD_REQUEST, format!("Invalid player count: {} (need 2-8)", count))
+            AppError::InvalidPlayerCount { count, allowed } => {
+                (
+                    StatusCode::BAD_REQUEST,
+                    format!("Invalid player count: {} (need {}-{})", count, allowed.start(), allowed.end()),
+                )
             }
             AppError::InvalidRoomId => {
                 (StatusCode::BAD_REQUEST, "Invalid room ID".to_string())
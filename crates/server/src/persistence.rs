@@ -0,0 +1,103 @@
+//! Room persistence across restarts.
+//!
+//! All rooms (including room codes) are written to a JSON file every few
+//! seconds and once more on graceful shutdown, then loaded again at startup,
+//! so a deploy does not wipe games in progress.
+
+use crate::AppState;
+use big_picture_domain::RoomsSnapshot;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Default file rooms are saved to.
+const DEFAULT_STATE_FILE: &str = "room_state.json";
+
+/// Default interval between periodic saves.
+const DEFAULT_SAVE_SECONDS: u64 = 15;
+
+/// Read `STATE_FILE` (empty disables persistence).
+pub fn state_file_from_env() -> Option<PathBuf> {
+    let path = std::env::var("STATE_FILE").unwrap_or_else(|_| DEFAULT_STATE_FILE.to_string());
+    (!path.is_empty()).then(|| PathBuf::from(path))
+}
+
+/// Load saved rooms, if the file exists and parses.
+pub fn load(path: &Path) -> Option<RoomsSnapshot> {
+    let data = match std::fs::read(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            tracing::error!("Failed to read saved rooms from {}: {}", path.display(), e);
+            return None;
+        }
+    };
+
+    match serde_json::from_slice(&data) {
+        Ok(snapshot) => Some(snapshot),
+        Err(e) => {
+            tracing::error!("Ignoring unreadable saved rooms in {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Write every room to `path`.
+///
+/// Writes to a temporary file first so a crash never leaves a truncated save.
+pub async fn save(state: &AppState, path: &Path) -> std::io::Result<()> {
+    let snapshot = state.room_manager.read().await.snapshot();
+    let data = serde_json::to_vec(&snapshot)?;
+
+    let tmp = path.with_extension("tmp");
+    tokio::fs::write(&tmp, data).await?;
+    tokio::fs::rename(&tmp, path).await?;
+
+    tracing::debug!("Saved {} rooms to {}", snapshot.rooms.len(), path.display());
+    Ok(())
+}
+
+/// Save rooms periodically (every `SAVE_SECONDS`, default 15).
+pub fn spawn_autosave(state: AppState, path: PathBuf) {
+    let seconds = std::env::var("SAVE_SECONDS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_SAVE_SECONDS);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(seconds.max(1)));
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            if let Err(e) = save(&state, &path).await {
+                tracing::error!("Failed to save rooms to {}: {}", path.display(), e);
+            }
+        }
+    });
+}
+
+/// Resolve on Ctrl+C or SIGTERM.
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    tracing::info!("Shutting down...");
+}
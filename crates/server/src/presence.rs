@@ -0,0 +1,53 @@
+//! Presence tracking.
+//!
+//! Clients prove they are still there by keeping an authenticated WebSocket
+//! open (`/rooms/:room_id/ws?token=...`) or by calling
+//! `POST /rooms/:room_id/heartbeat`. A background sweeper marks anyone not
+//! heard from within the grace period as disconnected; the next sign of life
+//! reconnects them.
+
+use crate::stage_timer::unix_now;
+use crate::AppState;
+use big_picture_domain::{Participant, RoomId};
+use std::time::Duration;
+
+/// Default seconds without a sign of life before someone counts as disconnected.
+const DEFAULT_GRACE_SECONDS: u64 = 15;
+
+/// How often open sockets refresh their participant's presence.
+pub const SOCKET_TOUCH: Duration = Duration::from_secs(5);
+
+/// How often the sweeper looks for silent participants.
+const SWEEP: Duration = Duration::from_secs(1);
+
+/// Read `PRESENCE_GRACE_SECONDS` (default 15).
+pub fn grace_from_env() -> u64 {
+    std::env::var("PRESENCE_GRACE_SECONDS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_GRACE_SECONDS)
+}
+
+/// Disconnect participants silent for longer than `grace` seconds.
+pub fn spawn_sweeper(state: AppState, grace: u64) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SWEEP);
+        loop {
+            interval.tick().await;
+            let mut manager = state.room_manager.write().await;
+            for (room_id, participant) in manager.sweep_presence(unix_now(), grace) {
+                tracing::info!("{:?} in room {} went silent, marked disconnected", participant, room_id);
+            }
+            state.publish(&mut manager);
+        }
+    });
+}
+
+/// Note a sign of life from a participant.
+pub async fn touch(state: &AppState, room_id: RoomId, participant: Participant) {
+    let mut manager = state.room_manager.write().await;
+    if let Err(e) = manager.touch(room_id, participant, unix_now()) {
+        tracing::debug!("Ignoring presence of {:?} in room {}: {}", participant, room_id, e);
+    }
+    state.publish(&mut manager);
+}
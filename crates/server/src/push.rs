@@ -0,0 +1,246 @@
+//! WebSocket push channel for room and game state.
+//!
+//! `GET /rooms/:room_id/ws` sends a full room snapshot on connect and then
+//! forwards every [`RoomEvent`] the RoomManager queues for that room. Handlers
+//! call [`AppState::publish`] while still holding the write lock, so events
+//! reach subscribers in the order they happened and a snapshot taken under the
+//! read lock never misses or repeats one.
+//!
+//! Clients that pass their session token (`?token=...`) count as present for
+//! as long as the socket stays open (see `presence`).
+//!
+//! Messages are JSON objects tagged by `type`. Besides the event types, the
+//! server sends `{"type": "snapshot", "room": ...}`; clients may send the text
+//! `snapshot` at any time to ask for a fresh one.
+//!
+//! The [`EventHub`] numbers each room's events and keeps the most recent ones
+//! so the Server-Sent Events stream (see `event_stream`) can resume from a
+//! `Last-Event-ID`.
+
+use crate::{presence, room_snapshot, AppError, AppState};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, State};
+use axum::response::Response;
+use big_picture_domain::{Participant, RoomError, RoomEvent, RoomId, RoomManager, SessionToken};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+/// Events buffered per room before a slow subscriber is resynced with a snapshot.
+const CHANNEL_CAPACITY: usize = 64;
+
+/// Recent events kept per room for clients resuming after a disconnect.
+const REPLAY_CAPACITY: usize = 256;
+
+/// A serialized event and its position in the room's event sequence.
+#[derive(Debug, Clone)]
+pub struct PushedEvent {
+    /// Sequence number, starting at 1 for each room.
+    pub id: u64,
+    /// The event as JSON.
+    pub json: Arc<str>,
+}
+
+/// Broadcast channel and replay buffer of one room.
+#[derive(Debug)]
+struct RoomChannel {
+    sender: broadcast::Sender<PushedEvent>,
+    last_id: u64,
+    recent: VecDeque<PushedEvent>,
+}
+
+impl RoomChannel {
+    fn new() -> Self {
+        Self {
+            sender: broadcast::channel(CHANNEL_CAPACITY).0,
+            last_id: 0,
+            recent: VecDeque::with_capacity(REPLAY_CAPACITY),
+        }
+    }
+
+    /// Buffered events after `after`, or `None` if some are no longer buffered.
+    fn since(&self, after: u64) -> Option<Vec<PushedEvent>> {
+        let oldest = self.recent.front().map_or(self.last_id + 1, |e| e.id);
+        if after > self.last_id || after + 1 < oldest {
+            return None;
+        }
+        Some(self.recent.iter().filter(|e| e.id > after).cloned().collect())
+    }
+}
+
+/// A live subscription to a room's events.
+pub struct Subscription {
+    pub events: broadcast::Receiver<PushedEvent>,
+    /// ID of the newest event sent before the subscription started.
+    pub last_id: u64,
+    /// Events missed since the requested ID, if they could all be replayed.
+    pub missed: Option<Vec<PushedEvent>>,
+}
+
+/// Per-room broadcast channels of serialized events.
+#[derive(Debug, Default)]
+pub struct EventHub {
+    channels: Mutex<HashMap<RoomId, RoomChannel>>,
+}
+
+impl EventHub {
+    /// Subscribe to a room's events, replaying those after `after` if possible.
+    ///
+    /// Call while holding the RoomManager lock so the subscription lines up
+    /// with any snapshot taken alongside it.
+    pub fn subscribe(&self, room_id: RoomId, after: Option<u64>) -> Subscription {
+        let mut channels = self.channels.lock().unwrap();
+        let channel = channels.entry(room_id).or_insert_with(RoomChannel::new);
+        Subscription {
+            events: channel.sender.subscribe(),
+            last_id: channel.last_id,
+            missed: after.and_then(|after| channel.since(after)),
+        }
+    }
+
+    /// Number and record an event, then send it to the room's subscribers.
+    fn send(&self, room_id: RoomId, event: &RoomEvent) {
+        let json = match serde_json::to_string(event) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::error!("Failed to serialize event: {}", e);
+                return;
+            }
+        };
+        
+        let mut channels = self.channels.lock().unwrap();
+        if matches!(event, RoomEvent::RoomClosed) {
+            // Dropping the sender ends every subscriber's stream after this event
+            if let Some(channel) = channels.remove(&room_id) {
+                let _ = channel.sender.send(PushedEvent { id: channel.last_id + 1, json: json.into() });
+            }
+            return;
+        }
+        
+        let channel = channels.entry(room_id).or_insert_with(RoomChannel::new);
+        channel.last_id += 1;
+        let event = PushedEvent { id: channel.last_id, json: json.into() };
+        if channel.recent.len() == REPLAY_CAPACITY {
+            channel.recent.pop_front();
+        }
+        channel.recent.push_back(event.clone());
+        // Sending only fails when nobody is listening
+        let _ = channel.sender.send(event);
+    }
+}
+
+impl AppState {
+    /// Push the events queued by the last mutation to WebSocket subscribers.
+    ///
+    /// Call while holding the RoomManager write lock.
+    pub fn publish(&self, manager: &mut RoomManager) {
+        for (room_id, event) in manager.take_events() {
+            self.events.send(room_id, &event);
+        }
+    }
+}
+
+/// Query parameters of the WebSocket upgrade.
+#[derive(Debug, Deserialize)]
+pub struct SocketParams {
+    /// Session token of the display or player holding the socket, if any.
+    token: Option<String>,
+}
+
+/// GET /rooms/:room_id/ws - Subscribe to room updates.
+pub async fn room_socket(
+    State(state): State<AppState>,
+    Path(room_id_str): Path<String>,
+    Query(params): Query<SocketParams>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, AppError> {
+    let room_id = RoomId::from_string(&room_id_str)
+        .map_err(|_| AppError::InvalidRoomId)?;
+
+    let manager = state.room_manager.read().await;
+    if manager.get_room(&room_id).is_none() {
+        return Err(RoomError::RoomNotFound.into());
+    }
+    let participant = match params.token {
+        Some(token) => Some(manager.authenticate(&room_id, &SessionToken::new(token))?.participant),
+        None => None,
+    };
+    drop(manager);
+
+    Ok(ws.on_upgrade(move |socket| stream_room(state, room_id, participant, socket)))
+}
+
+/// Forward a room's snapshot and events to one client until either side closes.
+///
+/// Keeps `participant` present while the socket is open.
+async fn stream_room(state: AppState, room_id: RoomId, participant: Option<Participant>, mut socket: WebSocket) {
+    let mut presence = tokio::time::interval(presence::SOCKET_TOUCH);
+
+    let (snapshot, events) = {
+        let manager = state.room_manager.read().await;
+        let snapshot = snapshot_message(&state, &manager, room_id);
+        // Subscribe under the lock so no event falls between snapshot and stream
+        let events = manager.get_room(&room_id).map(|_| state.events.subscribe(room_id, None).events);
+        (snapshot, events)
+    };
+    if socket.send(snapshot).await.is_err() {
+        return;
+    }
+    let Some(mut events) = events else { return };
+
+    loop {
+        tokio::select! {
+            _ = presence.tick(), if participant.is_some() => {
+                if let Some(participant) = participant {
+                    presence::touch(&state, room_id, participant).await;
+                }
+            }
+            event = events.recv() => {
+                let message = match event {
+                    Ok(event) => Message::Text(event.json.to_string()),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::debug!("Subscriber of room {} lagged by {} events", room_id, skipped);
+                        // Resync: a fresh snapshot plus a fresh subscription, taken together
+                        let manager = state.room_manager.read().await;
+                        events = state.events.subscribe(room_id, None).events;
+                        snapshot_message(&state, &manager, room_id)
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                if socket.send(message).await.is_err() {
+                    return;
+                }
+            }
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Text(text))) if text.trim() == "snapshot" => {
+                    let message = {
+                        let manager = state.room_manager.read().await;
+                        snapshot_message(&state, &manager, room_id)
+                    };
+                    if socket.send(message).await.is_err() {
+                        return;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    let _ = socket.send(Message::Close(None)).await;
+}
+
+fn snapshot_message(state: &AppState, manager: &RoomManager, room_id: RoomId) -> Message {
+    Message::Text(snapshot_json(state, manager, room_id))
+}
+
+/// Serialize the room's current state, or a `room_closed` event if it is gone.
+pub fn snapshot_json(state: &AppState, manager: &RoomManager, room_id: RoomId) -> String {
+    let message = match room_snapshot(state, manager, &room_id) {
+        Ok(room) => json!({ "type": "snapshot", "room": room }),
+        Err(_) => json!(RoomEvent::RoomClosed),
+    };
+    message.to_string()
+}
@@ -0,0 +1,64 @@
+//! Server-authoritative stage timer.
+//!
+//! One background task runs per active game. It ends the goal reveal and voting
+//! after the room's configured periods and skips the current player's turn
+//! once its deadline passes, so neither a TV-side timer nor a dead phone is
+//! needed to progress.
+
+use crate::AppState;
+use big_picture_domain::game::{Expiry, GameStage};
+use big_picture_domain::{RoomId, RoomState};
+use std::time::Duration;
+
+/// How often each room checks its stage deadline.
+const TICK: Duration = Duration::from_millis(500);
+
+/// Spawn the stage timer for a room whose game has just started.
+pub fn spawn(state: AppState, room_id: RoomId) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TICK);
+        loop {
+            interval.tick().await;
+            let now = unix_now();
+
+            // Check under the read lock; only take the write lock when a deadline is due
+            let due = {
+                let manager = state.room_manager.read().await;
+                let Some(room) = manager.get_room(&room_id) else { break };
+                let Some(game) = room.game.as_ref() else { break };
+                if room.state != RoomState::InGame || game.stage == GameStage::Results {
+                    break;
+                }
+                game.is_stage_expired(now)
+            };
+            if !due {
+                continue;
+            }
+
+            let mut manager = state.room_manager.write().await;
+            match manager.advance_timers(&room_id, now) {
+                Ok(Some(Expiry::RevealEnded)) => {
+                    tracing::info!("Goal reveal in room {} ended", room_id);
+                }
+                Ok(Some(Expiry::VotingEnded)) => {
+                    tracing::info!("Voting in room {} ended", room_id);
+                }
+                Ok(Some(Expiry::TurnSkipped(player_id))) => {
+                    tracing::info!("Turn of player {} in room {} expired", player_id, room_id);
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Failed to advance timers in room {}: {}", room_id, e),
+            }
+            state.publish(&mut manager);
+        }
+        tracing::debug!("Stage timer for room {} stopped", room_id);
+    });
+}
+
+/// Current Unix time in seconds.
+pub fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}